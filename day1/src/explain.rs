@@ -0,0 +1,166 @@
+use std::fmt::{Display, Write};
+
+use crate::{first_and_last, scanner::Token, vocabulary::Vocabulary};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Digit,
+    Word,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Digit => f.pad("digit"),
+            Source::Word => f.pad("word"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'a> {
+    pub text: &'a str,
+    // in chars from the start of the line
+    pub column: usize,
+    pub digit: u32,
+    pub source: Source,
+}
+
+impl<'a> Match<'a> {
+    fn new(line: &'a str, token: Token) -> Self {
+        let text = token.text(line);
+        let source = if text.len() == 1 && text.starts_with(|c: char| c.is_ascii_digit()) {
+            Source::Digit
+        } else {
+            Source::Word
+        };
+
+        Self {
+            text,
+            column: line[..token.position].chars().count(),
+            digit: token.digit,
+            source,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation<'a> {
+    // counting from 1 like an editor would
+    pub line_number: usize,
+    pub line: &'a str,
+    // None when the line has nothing to decode
+    pub matches: Option<(Match<'a>, Match<'a>)>,
+}
+
+impl Explanation<'_> {
+    pub fn value(&self) -> Option<u64> {
+        self.matches
+            .map(|(first, last)| (first.digit * 10 + last.digit) as u64)
+    }
+}
+
+pub fn explain<'a>(input: &'a str, vocabulary: &Vocabulary) -> Vec<Explanation<'a>> {
+    let scanner = vocabulary.scanner();
+
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| Explanation {
+            line_number: i + 1,
+            line,
+            matches: first_and_last(&scanner, line)
+                .map(|(first, last)| (Match::new(line, first), Match::new(line, last))),
+        })
+        .collect()
+}
+
+pub fn table(explanations: &[Explanation]) -> String {
+    let mut out = format!(
+        "{:>5} | {:<12} {:>4} {:<5} | {:<12} {:>4} {:<5} | {:>5}\n",
+        "line", "first", "col", "from", "last", "col", "from", "value"
+    );
+
+    for explanation in explanations {
+        let value = explanation
+            .value()
+            .map_or("-".to_string(), |value| value.to_string());
+        let _ = match explanation.matches {
+            Some((first, last)) => writeln!(
+                out,
+                "{:>5} | {:<12} {:>4} {:<5} | {:<12} {:>4} {:<5} | {:>5}",
+                explanation.line_number,
+                first.text,
+                first.column,
+                first.source,
+                last.text,
+                last.column,
+                last.source,
+                value
+            ),
+            None => writeln!(
+                out,
+                "{:>5} | {:<36} | {:>5}",
+                explanation.line_number, "no digits", value
+            ),
+        };
+    }
+
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_match(m: &Match) -> String {
+    format!(
+        r#"{{"text":{},"column":{},"digit":{},"source":"{}"}}"#,
+        json_string(m.text),
+        m.column,
+        m.digit,
+        m.source
+    )
+}
+
+pub fn json(explanations: &[Explanation]) -> String {
+    let rows = explanations
+        .iter()
+        .map(|explanation| {
+            let (first, last) = match &explanation.matches {
+                Some((first, last)) => (json_match(first), json_match(last)),
+                None => ("null".to_string(), "null".to_string()),
+            };
+            let value = explanation
+                .value()
+                .map_or("null".to_string(), |value| value.to_string());
+
+            format!(
+                r#"{{"line_number":{},"line":{},"first":{},"last":{},"value":{}}}"#,
+                explanation.line_number,
+                json_string(explanation.line),
+                first,
+                last,
+                value
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!("[\n  {}\n]", rows.join(",\n  "))
+}
@@ -1,85 +1,240 @@
-fn get_first(line: &str) -> char {
-    line.chars()
-        .find(|c| c.is_ascii_digit())
-        .expect("couldn't find first")
+use anyhow::{anyhow, Context, Result};
+
+mod explain;
+mod scanner;
+use scanner::{Scanner, Token};
+mod vocabulary;
+use vocabulary::Vocabulary;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Calibration {
+    total: u64,
+    // line numbers, counting from 1, that had no digits to decode
+    skipped: Vec<usize>,
 }
 
-fn get_last(line: &str) -> char {
-    line.chars()
-        .rev()
-        .find(|c| c.is_ascii_digit())
-        .expect("couldn't find last")
+// Sum the first and last digit of every line. Strict mode gives up on the
+// first line without a digit, lenient mode notes it down and carries on.
+fn calibrate(s: &str, scanner: &Scanner, lenient: bool) -> Result<Calibration> {
+    let mut calibration = Calibration::default();
+
+    // numbered before blank lines are dropped so they match the file
+    for (i, line) in s.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match first_and_last(scanner, line) {
+            Some((first, last)) => calibration.total += (first.digit * 10 + last.digit) as u64,
+            None if lenient => calibration.skipped.push(i + 1),
+            None => return Err(anyhow!("couldn't find a digit on line {}: {}", i + 1, line)),
+        }
+    }
+
+    Ok(calibration)
 }
 
-fn part1(s: &str) -> u64 {
-    s.trim()
-        .split("\n")
-        .map(|line| {
-            let first = get_first(line);
-            let last = get_last(line);
-
-            format!("{}{}", first, last)
-                .parse::<u64>()
-                .expect("couldn't get value")
-        })
-        .sum()
+fn part1(s: &str, lenient: bool) -> Result<Calibration> {
+    calibrate(s, &Vocabulary::empty().scanner(), lenient)
 }
 
-const NUMBER_STRINGS: [&'static str; 9] = [
-    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-];
+fn first_and_last(scanner: &Scanner, line: &str) -> Option<(Token, Token)> {
+    let mut tokens = scanner.scan(line);
+    let mut first = tokens.next()?;
+    let mut last = first;
+
+    // matches come out in order of where they end, so a long word can turn up
+    // after a shorter one that starts later. Where two start together the
+    // longer wins, so IX is nine rather than the I inside it.
+    for token in tokens {
+        if (token.position, std::cmp::Reverse(token.len))
+            < (first.position, std::cmp::Reverse(first.len))
+        {
+            first = token;
+        }
+        if (token.position, token.len) > (last.position, last.len) {
+            last = token;
+        }
+    }
 
-fn starts_with_string(s: &str) -> Option<usize> {
-    NUMBER_STRINGS
-        .into_iter()
-        .position(|num| s.starts_with(num))
+    Some((first, last))
 }
 
-fn ends_with_string(s: &str) -> Option<usize> {
-    NUMBER_STRINGS.into_iter().position(|num| s.ends_with(num))
+fn part2(s: &str, vocabulary: &Vocabulary, lenient: bool) -> Result<Calibration> {
+    calibrate(s, &vocabulary.scanner(), lenient)
 }
 
-fn find_first<'a>(line: &'a str) -> String {
-    if line.starts_with(|c: char| c.is_ascii_digit()) {
-        line[0..1].into()
-    } else if let Some(pos) = starts_with_string(line) {
-        format!("{}", pos + 1)
-    } else {
-        find_first(&line[1..])
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+
+    let mut vocabulary = Vocabulary::default();
+    let mut ignore_case = false;
+    let mut lenient = false;
+    let mut explain: Option<fn(&[explain::Explanation]) -> String> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--vocabulary" => {
+                let path = args.next().context("--vocabulary needs a path")?;
+                vocabulary = std::fs::read_to_string(&path)
+                    .context(format!("couldn't read vocabulary {}", path))?
+                    .parse()?;
+            }
+            "--ignore-case" => ignore_case = true,
+            "--lenient" => lenient = true,
+            "--explain" => explain = Some(explain::table),
+            "--explain-json" => explain = Some(explain::json),
+            _ => return Err(anyhow!("unknown argument: {}", arg)),
+        }
     }
-}
+    let vocabulary = vocabulary.case_insensitive(ignore_case);
 
-fn find_last<'a>(line: &'a str) -> String {
-    dbg!(&line);
-    if line.ends_with(|c: char| c.is_ascii_digit()) {
-        line.chars().last().unwrap().into()
-    } else if let Some(pos) = ends_with_string(line) {
-        format!("{}", pos + 1)
-    } else {
-        let len = match line.len().checked_sub(1) {
-            Some(l) => l,
-            None => panic!("couldn't get for line {}", line),
-        };
-        find_last(&line[..len])
+    if let Some(format) = explain {
+        println!("{}", format(&explain::explain(input, &vocabulary)));
+        return Ok(());
     }
+
+    let report = |part: &str, calibration: Calibration| {
+        println!("{}: {}", part, calibration.total);
+        if !calibration.skipped.is_empty() {
+            eprintln!("{}: skipped lines {:?}", part, calibration.skipped);
+        }
+    };
+    report("part1", part1(input, lenient)?);
+    report("part2", part2(input, &vocabulary, lenient)?);
+    Ok(())
 }
 
-fn part2(s: &str) -> u64 {
-    s.trim()
-        .split("\n")
-        .map(|line| {
-            let first = find_first(line);
-            let last = find_last(line);
-
-            format!("{}{}", first, last)
-                .parse::<u64>()
-                .expect("couldn't get value")
-        })
-        .sum()
+#[test]
+fn part2_works() {
+    let input = r#"two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen"#;
+    assert_eq!(
+        part2(input, &Vocabulary::default(), false).unwrap().total,
+        281
+    );
 }
 
-fn main() {
-    let input = include_str!("../input.txt");
-    println!("part1: {}", part1(input));
-    println!("part2: {}", part2(input));
+#[test]
+fn overlapping_words_work() {
+    let vocabulary = Vocabulary::default();
+    assert_eq!(part2("eightwo", &vocabulary, false).unwrap().total, 82);
+    assert_eq!(part2("oneight", &vocabulary, false).unwrap().total, 18);
+    assert_eq!(part2("twone", &vocabulary, false).unwrap().total, 21);
+
+    let line = "x".repeat(1_000_000) + "sevenine";
+    assert_eq!(part2(&line, &vocabulary, false).unwrap().total, 79);
+}
+
+#[test]
+fn custom_vocabulary_works() {
+    let vocabulary = r#"# roman numerals and some german
+zero = 0
+IV = 4
+IX = 9
+drei = 3
+fünf = 5"#
+        .parse::<Vocabulary>()
+        .unwrap();
+    assert_eq!(part2("zeroxIXfive", &vocabulary, false).unwrap().total, 9);
+    assert_eq!(part2("äfünfdreiIV", &vocabulary, false).unwrap().total, 54);
+    assert_eq!(part2("Drei4ZERO", &vocabulary, false).unwrap().total, 44);
+
+    let vocabulary = vocabulary.case_insensitive(true);
+    assert_eq!(part2("Drei4ZERO", &vocabulary, false).unwrap().total, 30);
+    assert_eq!(part2("xFÜNFx", &vocabulary, false).unwrap().total, 55);
+
+    assert!("ten = 10".parse::<Vocabulary>().is_err());
+    assert!("ten".parse::<Vocabulary>().is_err());
+}
+
+#[test]
+fn explain_works() {
+    let input = "xtwone3four\nno digits here";
+    let explanations = explain::explain(input, &Vocabulary::default());
+
+    let (first, last) = explanations[0].matches.unwrap();
+    assert_eq!(
+        (first.text, first.column, first.source),
+        ("two", 1, explain::Source::Word)
+    );
+    assert_eq!(
+        (last.text, last.column, last.source),
+        ("four", 7, explain::Source::Word)
+    );
+    assert_eq!(explanations[0].value(), Some(24));
+    assert_eq!(explanations[1].matches, None);
+
+    assert_eq!(
+        explain::json(&explanations[..1]),
+        r#"[
+  {"line_number":1,"line":"xtwone3four","first":{"text":"two","column":1,"digit":2,"source":"word"},"last":{"text":"four","column":7,"digit":4,"source":"word"},"value":24}
+]"#
+    );
+}
+
+#[test]
+fn multibyte_and_missing_digits_work() {
+    let input = "é1ü2ñ\nnothing here\nsieben→3←eight\n∅";
+    let vocabulary = Vocabulary::default();
+
+    let error = part2(input, &vocabulary, false).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "couldn't find a digit on line 2: nothing here"
+    );
+
+    let calibration = part2(input, &vocabulary, true).unwrap();
+    assert_eq!(
+        calibration,
+        Calibration {
+            total: 12 + 38,
+            skipped: vec![2, 4],
+        }
+    );
+
+    let calibration = part1(input, true).unwrap();
+    assert_eq!(calibration.total, 12 + 33);
+}
+
+#[test]
+fn blank_lines_keep_line_numbers() {
+    let input = "\n\nabc\n12\n\n  \nnope\n3";
+    let vocabulary = Vocabulary::default();
+
+    let calibration = part2(input, &vocabulary, true).unwrap();
+    assert_eq!(
+        calibration,
+        Calibration {
+            total: 12 + 33,
+            skipped: vec![3, 7],
+        }
+    );
+    assert_eq!(
+        part2(input, &vocabulary, false).unwrap_err().to_string(),
+        "couldn't find a digit on line 3: abc"
+    );
+
+    let numbers = explain::explain(input, &vocabulary)
+        .iter()
+        .map(|explanation| explanation.line_number)
+        .collect::<Vec<_>>();
+    assert_eq!(numbers, vec![3, 4, 7, 8]);
+}
+
+#[test]
+fn longest_word_at_a_position_wins() {
+    let vocabulary = "I = 1\nIV = 4\nV = 5\nIX = 9"
+        .parse::<Vocabulary>()
+        .unwrap();
+    // the V still counts as the last word, like the two in eightwo
+    assert_eq!(part2("IV", &vocabulary, false).unwrap().total, 45);
+    assert_eq!(part2("xIX", &vocabulary, false).unwrap().total, 99);
+    assert_eq!(part2("IXxV", &vocabulary, false).unwrap().total, 95);
+    assert_eq!(part2("VxIIX", &vocabulary, false).unwrap().total, 59);
 }
@@ -0,0 +1,154 @@
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    // byte offset of the first char of the match
+    pub position: usize,
+    // length of the match in bytes
+    pub len: usize,
+    pub digit: u32,
+}
+
+impl Token {
+    pub fn text<'a>(&self, line: &'a str) -> &'a str {
+        &line[self.position..self.position + self.len]
+    }
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    next: HashMap<char, usize>,
+    fail: usize,
+    // (length in chars, digit) of every pattern ending here, including via fail links
+    outputs: Vec<(usize, u32)>,
+}
+
+// Aho–Corasick automaton over every pattern at once, so a line is read a
+// single time no matter how many words there are and overlapping matches
+// like `eightwo` all come out.
+#[derive(Debug)]
+pub struct Scanner {
+    nodes: Vec<Node>,
+    case_insensitive: bool,
+    longest: usize,
+}
+
+impl Scanner {
+    pub fn new<'a>(
+        patterns: impl IntoIterator<Item = (&'a str, u32)>,
+        case_insensitive: bool,
+    ) -> Self {
+        let mut scanner = Self {
+            nodes: vec![Node::default()],
+            case_insensitive,
+            longest: 0,
+        };
+
+        for (pattern, digit) in patterns {
+            let mut state = 0;
+            for c in pattern.chars() {
+                let c = scanner.fold(c);
+                state = match scanner.nodes[state].next.get(&c) {
+                    Some(next) => *next,
+                    None => {
+                        scanner.nodes.push(Node::default());
+                        let next = scanner.nodes.len() - 1;
+                        scanner.nodes[state].next.insert(c, next);
+                        next
+                    }
+                };
+            }
+
+            let len = pattern.chars().count();
+            scanner.longest = scanner.longest.max(len);
+            scanner.nodes[state].outputs.push((len, digit));
+        }
+
+        scanner.link();
+        scanner
+    }
+
+    // fail links point at the longest proper suffix that is also a prefix,
+    // filled in breadth first so shorter suffixes are always ready
+    fn link(&mut self) {
+        let nodes = &mut self.nodes;
+        let mut queue: VecDeque<usize> = nodes[0].next.values().copied().collect();
+
+        while let Some(state) = queue.pop_front() {
+            let children = nodes[state]
+                .next
+                .iter()
+                .map(|(c, next)| (*c, *next))
+                .collect::<Vec<_>>();
+
+            for (c, child) in children {
+                let mut fail = nodes[state].fail;
+                let fail = loop {
+                    if let Some(next) = nodes[fail].next.get(&c) {
+                        break *next;
+                    }
+                    if fail == 0 {
+                        break 0;
+                    }
+                    fail = nodes[fail].fail;
+                };
+
+                nodes[child].fail = fail;
+                let inherited = nodes[fail].outputs.clone();
+                nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+    }
+
+    fn fold(&self, c: char) -> char {
+        if self.case_insensitive {
+            c.to_lowercase().next().unwrap_or(c)
+        } else {
+            c
+        }
+    }
+
+    fn step(&self, mut state: usize, c: char) -> usize {
+        loop {
+            if let Some(next) = self.nodes[state].next.get(&c) {
+                return *next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+
+    // every match in the line, in order of where they end
+    pub fn scan<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Token> + 'a {
+        // byte offsets of the last few chars, enough to find where any match began
+        let starts = VecDeque::with_capacity(self.longest);
+
+        line.char_indices()
+            .scan((0, starts), move |(state, starts), (i, c)| {
+                *state = self.step(*state, self.fold(c));
+                if starts.len() >= self.longest.max(1) {
+                    starts.pop_front();
+                }
+                starts.push_back(i);
+                let end = i + c.len_utf8();
+
+                let tokens = self.nodes[*state]
+                    .outputs
+                    .iter()
+                    .map(|(len, digit)| {
+                        let position = starts[starts.len() - len];
+                        Token {
+                            position,
+                            len: end - position,
+                            digit: *digit,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                Some(tokens)
+            })
+            .flatten()
+    }
+}
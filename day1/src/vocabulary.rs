@@ -0,0 +1,94 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Error, Result};
+
+use crate::scanner::Scanner;
+
+const NUMBER_STRINGS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+// The words that count as digits on top of the digits themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+    case_insensitive: bool,
+}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        let mut vocabulary = Self::empty();
+        for (word, digit) in NUMBER_STRINGS.into_iter().zip(1..) {
+            vocabulary
+                .add(word, digit)
+                .expect("english words are valid");
+        }
+        vocabulary
+    }
+}
+
+impl FromStr for Vocabulary {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // one word per line, blank lines and # comments skipped
+        // zero = 0
+        let mut vocabulary = Self::empty();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (word, digit) = line
+                .split_once('=')
+                .context(format!("couldn't get word and digit on line {}", i + 1))?;
+            let digit = digit
+                .trim()
+                .parse::<u32>()
+                .context(format!("couldn't parse digit on line {}", i + 1))?;
+            vocabulary
+                .add(word.trim(), digit)
+                .context(format!("bad entry on line {}", i + 1))?;
+        }
+
+        Ok(vocabulary)
+    }
+}
+
+impl Vocabulary {
+    pub fn empty() -> Self {
+        Self {
+            words: vec![],
+            case_insensitive: false,
+        }
+    }
+
+    pub fn add(&mut self, word: &str, digit: u32) -> Result<()> {
+        if word.is_empty() {
+            return Err(anyhow!("word can't be empty"));
+        }
+        if digit > 9 {
+            return Err(anyhow!("{} isn't a single digit", digit));
+        }
+
+        self.words.push((word.into(), digit));
+        Ok(())
+    }
+
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    pub fn scanner(&self) -> Scanner {
+        let digits = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+        let patterns = digits.into_iter().zip(0..).chain(
+            self.words
+                .iter()
+                .map(|(word, digit)| (word.as_str(), *digit)),
+        );
+
+        Scanner::new(patterns, self.case_insensitive)
+    }
+}
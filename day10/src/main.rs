@@ -0,0 +1,314 @@
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::{anyhow, Context, Error, Result};
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+    println!("part1: {}", part1(input)?);
+    println!("part2: {}", part2(input)?);
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
+struct Coord(isize, isize);
+
+impl Coord {
+    fn step(self, dir: Dir) -> Coord {
+        let Coord(x, y) = self;
+        match dir {
+            Dir::North => Coord(x, y - 1),
+            Dir::South => Coord(x, y + 1),
+            Dir::East => Coord(x + 1, y),
+            Dir::West => Coord(x - 1, y),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+enum Dir {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Dir {
+    const ALL: [Dir; 4] = [Dir::North, Dir::South, Dir::East, Dir::West];
+
+    fn opposite(self) -> Dir {
+        match self {
+            Dir::North => Dir::South,
+            Dir::South => Dir::North,
+            Dir::East => Dir::West,
+            Dir::West => Dir::East,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Tile {
+    Vertical,
+    Horizontal,
+    NorthEast,
+    NorthWest,
+    SouthWest,
+    SouthEast,
+    Ground,
+    Start,
+}
+
+impl TryFrom<char> for Tile {
+    type Error = Error;
+
+    fn try_from(value: char) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
+            '|' => Tile::Vertical,
+            '-' => Tile::Horizontal,
+            'L' => Tile::NorthEast,
+            'J' => Tile::NorthWest,
+            '7' => Tile::SouthWest,
+            'F' => Tile::SouthEast,
+            '.' => Tile::Ground,
+            'S' => Tile::Start,
+            _ => return Err(anyhow!("unknown tile: {}", value)),
+        })
+    }
+}
+
+impl Tile {
+    const PIPES: [Tile; 6] = [
+        Tile::Vertical,
+        Tile::Horizontal,
+        Tile::NorthEast,
+        Tile::NorthWest,
+        Tile::SouthWest,
+        Tile::SouthEast,
+    ];
+
+    fn connections(self) -> Option<[Dir; 2]> {
+        Some(match self {
+            Tile::Vertical => [Dir::North, Dir::South],
+            Tile::Horizontal => [Dir::East, Dir::West],
+            Tile::NorthEast => [Dir::North, Dir::East],
+            Tile::NorthWest => [Dir::North, Dir::West],
+            Tile::SouthWest => [Dir::South, Dir::West],
+            Tile::SouthEast => [Dir::South, Dir::East],
+            Tile::Ground | Tile::Start => return None,
+        })
+    }
+
+    fn connects(self, dir: Dir) -> bool {
+        self.connections()
+            .is_some_and(|connections| connections.contains(&dir))
+    }
+
+    // entering from the `from` side, which way does the pipe lead out
+    fn exit(self, from: Dir) -> Option<Dir> {
+        let [a, b] = self.connections()?;
+        if a == from {
+            Some(b)
+        } else if b == from {
+            Some(a)
+        } else {
+            None
+        }
+    }
+}
+
+struct Map {
+    inner: HashMap<Coord, Tile>,
+    start: Coord,
+}
+
+impl FromStr for Map {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut inner = HashMap::new();
+        let mut start = None;
+
+        for (y, line) in s.trim().lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let coord = Coord(x as isize, y as isize);
+                let tile = Tile::try_from(c)?;
+                if tile == Tile::Start {
+                    start = Some(coord);
+                }
+                inner.insert(coord, tile);
+            }
+        }
+
+        let start = start.context("couldn't find start")?;
+        let mut map = Self { inner, start };
+        let start_tile = map.start_tile()?;
+        map.inner.insert(start, start_tile);
+
+        Ok(map)
+    }
+}
+
+impl Map {
+    fn get(&self, coord: &Coord) -> Option<Tile> {
+        self.inner.get(coord).copied()
+    }
+
+    // more than two neighbours can point at S, so try each pipe they allow and
+    // keep the one whose walk comes back round to it
+    fn start_tile(&self) -> Result<Tile> {
+        let open = Dir::ALL
+            .into_iter()
+            .filter(|dir| {
+                self.get(&self.start.step(*dir))
+                    .is_some_and(|tile| tile.connects(dir.opposite()))
+            })
+            .collect::<Vec<_>>();
+
+        Tile::PIPES
+            .into_iter()
+            .filter(|tile| open.iter().filter(|dir| tile.connects(**dir)).count() == 2)
+            .find(|tile| self.walk(*tile).is_ok())
+            .context(format!("couldn't infer start from {:?}", open))
+    }
+
+    fn main_loop(&self) -> Result<Vec<Coord>> {
+        let start_tile = self.get(&self.start).context("couldn't get start")?;
+        self.walk(start_tile)
+    }
+
+    // follow the pipes out of S as if it were `start_tile`
+    fn walk(&self, start_tile: Tile) -> Result<Vec<Coord>> {
+        let [mut dir, back] = start_tile.connections().context("start isn't a pipe")?;
+        let mut head = self.start;
+        let mut path = vec![];
+
+        loop {
+            path.push(head);
+            head = head.step(dir);
+            if head == self.start {
+                if dir.opposite() != back {
+                    return Err(anyhow!(
+                        "loop came back into start from {:?}",
+                        dir.opposite()
+                    ));
+                }
+                return Ok(path);
+            }
+
+            let tile = self
+                .get(&head)
+                .context(format!("loop ran off the map at {:?}", head))?;
+            dir = tile
+                .exit(dir.opposite())
+                .context(format!("loop broken at {:?}", head))?;
+        }
+    }
+}
+
+fn part1(input: &str) -> Result<usize> {
+    let map = input.parse::<Map>()?;
+    Ok(map.main_loop()?.len() / 2)
+}
+
+fn part2(input: &str) -> Result<isize> {
+    let map = input.parse::<Map>()?;
+    let path = map.main_loop()?;
+
+    // shoelace for the area enclosed by the tile centres, then Pick's theorem
+    // A = i + b/2 - 1 to get the interior tiles
+    let double_area = path
+        .iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum::<isize>()
+        .abs();
+    let boundary = path.len() as isize;
+
+    Ok((double_area - boundary) / 2 + 1)
+}
+
+#[test]
+fn part1_works() {
+    let input = r#"-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF"#;
+    assert_eq!(part1(input).unwrap(), 4);
+
+    let input = r#"7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ"#;
+    assert_eq!(part1(input).unwrap(), 8);
+}
+
+#[test]
+fn part2_works() {
+    let input = r#"...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+..........."#;
+    assert_eq!(part2(input).unwrap(), 4);
+}
+
+#[test]
+fn part2_squeezing_works() {
+    let input = r#"..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+.........."#;
+    assert_eq!(part2(input).unwrap(), 4);
+}
+
+#[test]
+fn part2_larger_works() {
+    let input = r#".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ..."#;
+    assert_eq!(part2(input).unwrap(), 8);
+}
+
+#[test]
+fn part2_junk_works() {
+    let input = r#"FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L"#;
+    assert_eq!(part2(input).unwrap(), 10);
+}
+
+#[test]
+fn crowded_start_works() {
+    // west, east and south all point at S, but only south and east close the loop
+    let input = r#"-S-7
+.|.|
+.L-J"#;
+    assert_eq!(part1(input).unwrap(), 4);
+    assert_eq!(part2(input).unwrap(), 1);
+
+    assert!(part1("-S-\n...").is_err());
+}
@@ -0,0 +1,140 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::{Bag, Game};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub bag: Bag,
+    // the raw likelihood underflows to zero after a few hundred frames
+    pub ln_likelihood: f64,
+    // likelihood over the best bag's, so the best is 1
+    pub relative: f64,
+}
+
+// ordered worst first, so the top of a max heap is the one to drop
+struct Candidate {
+    ln_likelihood: f64,
+    total: usize,
+    counts: Vec<usize>,
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .ln_likelihood
+            .total_cmp(&self.ln_likelihood)
+            .then(self.total.cmp(&other.total))
+            .then(self.counts.cmp(&other.counts))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+// ln(n!) for every n up to `max`
+fn ln_factorials(max: usize) -> Vec<f64> {
+    let mut table = vec![0.0; max + 1];
+    for n in 1..=max {
+        table[n] = table[n - 1] + (n as f64).ln();
+    }
+    table
+}
+
+fn ln_choose(ln_factorials: &[f64], n: usize, k: usize) -> f64 {
+    ln_factorials[n] - ln_factorials[k] - ln_factorials[n - k]
+}
+
+// Each frame is one grab without replacement, so the chance of seeing it
+// from a bag is hypergeometric: the ways to pick each color's count out of
+// that color over the ways to pick the whole grab out of the whole bag.
+// Cubes go back between frames, so the frames multiply.
+fn ln_likelihood(game: &Game, colors: &[String], counts: &[usize], ln_factorials: &[f64]) -> f64 {
+    let total = counts.iter().sum::<usize>();
+
+    game.frames
+        .iter()
+        .map(|frame| {
+            let grab = frame.cubes.iter().map(|(_, num)| num).sum::<usize>();
+            let ways = frame
+                .cubes
+                .iter()
+                .map(|(color, num)| {
+                    let i = colors.iter().position(|c| c == color).unwrap();
+                    ln_choose(ln_factorials, counts[i], *num)
+                })
+                .sum::<f64>();
+            ways - ln_choose(ln_factorials, total, grab)
+        })
+        .sum()
+}
+
+// The `top` most likely bags holding at least the game's minimal bag and at
+// most `max_total` cubes, best first. Only colors the game shows are counted
+// since nothing can be said about the rest. Ties go to the smaller bag. Every
+// bag is looked at but only `top` are ever held on to.
+pub fn estimate(game: &Game, max_total: usize, top: usize) -> Vec<Estimate> {
+    let min = game.min_bag();
+    let colors = min.cubes.keys().cloned().collect::<Vec<_>>();
+    let floor = min.cubes.values().copied().collect::<Vec<_>>();
+    let ln_factorials = ln_factorials(max_total);
+
+    let mut counts = floor.clone();
+    if top == 0 || counts.iter().sum::<usize>() > max_total {
+        return vec![];
+    }
+
+    let mut best = BinaryHeap::with_capacity(top + 1);
+    // odometer over the counts, rolling a color back to its floor once the
+    // bag would go over `max_total`
+    loop {
+        best.push(Candidate {
+            ln_likelihood: ln_likelihood(game, &colors, &counts, &ln_factorials),
+            total: counts.iter().sum(),
+            counts: counts.clone(),
+        });
+        if best.len() > top {
+            best.pop();
+        }
+
+        let mut i = 0;
+        loop {
+            if i == counts.len() {
+                break;
+            }
+            counts[i] += 1;
+            if counts.iter().sum::<usize>() <= max_total {
+                break;
+            }
+            counts[i] = floor[i];
+            i += 1;
+        }
+        if i == counts.len() {
+            break;
+        }
+    }
+
+    // sorted ascending, which with worse counting as greater is best first
+    let best = best.into_sorted_vec();
+    let ln_max = best[0].ln_likelihood;
+
+    best.into_iter()
+        .map(|candidate| Estimate {
+            bag: Bag {
+                cubes: colors.iter().cloned().zip(candidate.counts).collect(),
+            },
+            ln_likelihood: candidate.ln_likelihood,
+            relative: (candidate.ln_likelihood - ln_max).exp(),
+        })
+        .collect()
+}
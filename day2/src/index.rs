@@ -0,0 +1,135 @@
+use anyhow::{anyhow, Context, Result};
+
+use crate::{Bag, Game};
+
+// Per game per color maxima, worked out once so bag questions don't have to
+// walk every frame again. Rows line up with `ids`, columns with `colors`.
+pub struct Index {
+    colors: Vec<String>,
+    ids: Vec<usize>,
+    minima: Vec<Vec<usize>>,
+}
+
+impl Index {
+    pub fn new(games: &[Game]) -> Self {
+        let bags = games.iter().map(Game::min_bag).collect::<Vec<_>>();
+
+        let mut colors = bags
+            .iter()
+            .flat_map(|bag| bag.cubes.keys().cloned())
+            .collect::<Vec<_>>();
+        colors.sort();
+        colors.dedup();
+
+        let minima = bags
+            .iter()
+            .map(|bag| colors.iter().map(|color| bag.get(color)).collect())
+            .collect();
+
+        Self {
+            colors,
+            ids: games.iter().map(|game| game.id).collect(),
+            minima,
+        }
+    }
+
+    // every color any game showed
+    pub fn colors(&self) -> &[String] {
+        &self.colors
+    }
+
+    fn to_bag(&self, row: &[usize]) -> Bag {
+        Bag {
+            cubes: self
+                .colors
+                .iter()
+                .cloned()
+                .zip(row.iter().copied())
+                .filter(|(_, num)| *num > 0)
+                .collect(),
+        }
+    }
+
+    // ids of the games that could have been played with `bag`
+    pub fn possible(&self, bag: &Bag) -> Vec<usize> {
+        let limits = self
+            .colors
+            .iter()
+            .map(|color| bag.get(color))
+            .collect::<Vec<_>>();
+
+        self.ids
+            .iter()
+            .zip(&self.minima)
+            .filter(|(_, row)| row.iter().zip(&limits).all(|(min, limit)| min <= limit))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    // the smallest bag every one of the games could have been played with
+    pub fn covering_bag(&self, ids: &[usize]) -> Result<Bag> {
+        let mut cover = vec![0; self.colors.len()];
+        for id in ids {
+            let row = self
+                .ids
+                .iter()
+                .position(|game| game == id)
+                .context(format!("no game {}", id))?;
+            for (max, min) in cover.iter_mut().zip(&self.minima[row]) {
+                *max = (*max).max(*min);
+            }
+        }
+
+        Ok(self.to_bag(&cover))
+    }
+
+    // The minimal bags no other game's minimal bag beats in every color, each
+    // with the games it would let through. Any other game's minimal bag is at
+    // least as big as one of these in every color.
+    pub fn pareto_frontier(&self) -> Vec<(Bag, Vec<usize>)> {
+        let dominates = |a: &[usize], b: &[usize]| a != b && a.iter().zip(b).all(|(a, b)| a <= b);
+
+        let mut frontier: Vec<&Vec<usize>> = self
+            .minima
+            .iter()
+            .filter(|row| !self.minima.iter().any(|other| dominates(other, row)))
+            .collect();
+        frontier.sort();
+        frontier.dedup();
+
+        frontier
+            .into_iter()
+            .map(|row| {
+                let bag = self.to_bag(row);
+                let ids = self.possible(&bag);
+                (bag, ids)
+            })
+            .collect()
+    }
+
+    // possible 12 red, 13 green, 14 blue
+    // cover 1 2 3
+    // frontier
+    pub fn query(&self, line: &str) -> Result<String> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+        Ok(match command {
+            "possible" => format!("{:?}", self.possible(&rest.parse()?)),
+            "cover" => {
+                let ids = rest
+                    .split_whitespace()
+                    .map(|id| id.parse().context(format!("couldn't parse id {}", id)))
+                    .collect::<Result<Vec<_>>>()?;
+                format!("{}", self.covering_bag(&ids)?)
+            }
+            "frontier" => self
+                .pareto_frontier()
+                .into_iter()
+                .map(|(bag, ids)| format!("{} -> {:?}", bag, ids))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => return Err(anyhow!("unknown query: {}", line)),
+        })
+    }
+}
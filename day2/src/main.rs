@@ -1,41 +1,96 @@
-use anyhow::{Context, Error, Result};
-use std::str::FromStr;
+use anyhow::{anyhow, Context, Error, Result};
+use std::{collections::BTreeMap, fmt::Display, io::BufRead, str::FromStr};
 
-#[derive(Default)]
+mod estimate;
+mod index;
+mod simulate;
+use index::Index;
+
+const DEFAULT_BAG: &str = "12 red, 13 green, 14 blue";
+
+// 3 blue, 4 red
+fn parse_cubes(s: &str) -> Result<Vec<(String, usize)>> {
+    let mut cubes: Vec<(String, usize)> = Vec::new();
+    for tuple in s.trim().split(", ") {
+        let (num, color) = tuple.split_once(" ").context("can't split tuple")?;
+        let num = num
+            .parse::<usize>()
+            .context(format!("couldn't get set num {}", s))?;
+        if cubes.iter().any(|(seen, _)| seen == color) {
+            return Err(anyhow!("color {} listed twice in {}", color, s));
+        }
+        cubes.push((color.into(), num));
+    }
+
+    Ok(cubes)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 struct Frame {
-    r: usize,
-    g: usize,
-    b: usize,
+    // kept in the order they were listed
+    cubes: Vec<(String, usize)>,
 }
 
 impl FromStr for Frame {
     type Err = Error;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let mut frame: Frame = Default::default();
-        for tuple in s.trim().split(", ") {
-            let (num, color) = tuple.split_once(" ").context("can't split tuple")?;
-            let num = num
-                .parse::<usize>()
-                .context(format!("couldn't get set num {}", s))?;
-            match color {
-                "red" => frame.r = num,
-                "blue" => frame.b = num,
-                "green" => frame.g = num,
-                _ => return Err(anyhow::anyhow!("unknown color")),
-            }
-        }
+        Ok(Self {
+            cubes: parse_cubes(s)?,
+        })
+    }
+}
 
-        Ok(frame)
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cubes = self
+            .cubes
+            .iter()
+            .map(|(color, num)| format!("{} {}", num, color))
+            .collect::<Vec<_>>();
+        write!(f, "{}", cubes.join(", "))
     }
 }
 
 impl Frame {
-    fn is_possible(&self, r: usize, g: usize, b: usize) -> bool {
-        r >= self.r && g >= self.g && b >= self.b
+    fn is_possible(&self, bag: &Bag) -> bool {
+        self.cubes.iter().all(|(color, num)| bag.get(color) >= *num)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Bag {
+    cubes: BTreeMap<String, usize>,
+}
+
+impl FromStr for Bag {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self {
+            cubes: parse_cubes(s)?.into_iter().collect(),
+        })
+    }
+}
+
+impl Display for Bag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cubes = self
+            .cubes
+            .iter()
+            .map(|(color, num)| format!("{} {}", num, color))
+            .collect::<Vec<_>>();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+impl Bag {
+    fn get(&self, color: &str) -> usize {
+        self.cubes.get(color).copied().unwrap_or(0)
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Game {
     frames: Vec<Frame>,
     id: usize,
@@ -63,22 +118,39 @@ impl FromStr for Game {
     }
 }
 
+impl Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let frames = self
+            .frames
+            .iter()
+            .map(|frame| frame.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "Game {}: {}", self.id, frames.join("; "))
+    }
+}
+
 impl Game {
-    fn is_possible(&self, r: usize, g: usize, b: usize) -> bool {
-        self.frames.iter().all(|frame| frame.is_possible(r, g, b))
+    fn is_possible(&self, bag: &Bag) -> bool {
+        self.frames.iter().all(|frame| frame.is_possible(bag))
     }
 
-    fn min_power_possible(&self) -> usize {
-        let mut r = 0;
-        let mut g = 0;
-        let mut b = 0;
+    fn min_bag(&self) -> Bag {
+        let mut bag = Bag::default();
         for frame in &self.frames {
-            r = r.max(frame.r);
-            g = g.max(frame.g);
-            b = b.max(frame.b);
+            for (color, num) in &frame.cubes {
+                let max = bag.cubes.entry(color.clone()).or_insert(0);
+                *max = (*max).max(*num);
+            }
         }
 
-        r * g * b
+        bag
+    }
+
+    // multiplied over every color in `palette`, so one that never turns up
+    // in this game makes the power zero
+    fn min_power(&self, palette: &[String]) -> usize {
+        let min = self.min_bag();
+        palette.iter().map(|color| min.get(color)).product()
     }
 }
 
@@ -89,17 +161,225 @@ fn parse(s: &str) -> Result<Vec<Game>> {
 fn main() -> Result<()> {
     let input = include_str!("../input.txt");
     let games = parse(input)?;
+    let index = Index::new(&games);
+
+    let mut args = std::env::args().skip(1);
+    let arg = args.next();
+    if arg.as_deref() == Some("--simulate") {
+        // --simulate <seed> [bag]
+        let seed = args
+            .next()
+            .context("couldn't get seed")?
+            .parse::<u64>()
+            .context("couldn't parse seed")?;
+        let bag = args
+            .next()
+            .unwrap_or(DEFAULT_BAG.into())
+            .parse::<Bag>()
+            .context("couldn't parse bag")?;
+        for game in simulate::simulate(&bag, seed, 100)? {
+            println!("{}", game);
+        }
+        return Ok(());
+    }
+    if arg.as_deref() == Some("--estimate") {
+        // --estimate <id> [max cubes]
+        let id = args
+            .next()
+            .context("couldn't get game id")?
+            .parse::<usize>()
+            .context("couldn't parse game id")?;
+        let max_total = match args.next() {
+            Some(max) => max.parse::<usize>().context("couldn't parse max cubes")?,
+            None => 100,
+        };
+        let game = games
+            .iter()
+            .find(|g| g.id == id)
+            .context(format!("no game {}", id))?;
+        for estimate in estimate::estimate(game, max_total, 10) {
+            println!(
+                "{:>12.4} {:>10.6} {}",
+                estimate.ln_likelihood, estimate.relative, estimate.bag
+            );
+        }
+        return Ok(());
+    }
+    if arg.as_deref() == Some("--interactive") {
+        for line in std::io::stdin().lock().lines() {
+            match index.query(&line?) {
+                Ok(answer) => println!("{}", answer),
+                Err(e) => println!("error: {:#}", e),
+            }
+        }
+        return Ok(());
+    }
+
+    let bag = arg
+        .unwrap_or(DEFAULT_BAG.into())
+        .parse::<Bag>()
+        .context("couldn't parse bag")?;
     let part1 = games
         .iter()
-        .filter(|g| g.is_possible(12, 13, 14))
+        .filter(|g| g.is_possible(&bag))
         .map(|g| g.id)
         .sum::<usize>();
     println!("part1: {}", part1);
 
     let part2 = games
         .iter()
-        .map(|g| g.min_power_possible())
+        .map(|g| g.min_power(index.colors()))
         .sum::<usize>();
     println!("part2: {}", part2);
     Ok(())
 }
+
+#[cfg(test)]
+const EXAMPLE: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
+
+#[test]
+fn example_works() {
+    let games = parse(EXAMPLE).unwrap();
+    let bag = DEFAULT_BAG.parse::<Bag>().unwrap();
+
+    let ids = games
+        .iter()
+        .filter(|g| g.is_possible(&bag))
+        .map(|g| g.id)
+        .sum::<usize>();
+    assert_eq!(ids, 8);
+
+    let index = Index::new(&games);
+    let power = games
+        .iter()
+        .map(|g| g.min_power(index.colors()))
+        .sum::<usize>();
+    assert_eq!(power, 2286);
+}
+
+#[test]
+fn any_palette_works() {
+    let games = parse(
+        r#"Game 1: 2 cyan, 1 magenta; 4 yellow
+Game 2: 5 cyan; 1 black"#,
+    )
+    .unwrap();
+    let bag = "3 cyan, 2 magenta, 4 yellow".parse::<Bag>().unwrap();
+
+    assert!(games[0].is_possible(&bag));
+    // no black cubes at all in the bag
+    assert!(!games[1].is_possible(&bag));
+    // the power is over every color in the games, whatever bag was asked about
+    let index = Index::new(&games);
+    assert_eq!(index.colors(), ["black", "cyan", "magenta", "yellow"]);
+    assert_eq!(games[0].min_power(index.colors()), 0);
+    assert_eq!(games[0].min_power(&["cyan".into(), "yellow".into()]), 2 * 4);
+    assert_eq!(games[1].min_power(&["black".into(), "cyan".into()]), 5);
+
+    assert!("1 red, 2 red".parse::<Bag>().is_err());
+}
+
+#[test]
+fn index_works() {
+    let games = parse(EXAMPLE).unwrap();
+    let index = Index::new(&games);
+    let bag = DEFAULT_BAG.parse::<Bag>().unwrap();
+
+    assert_eq!(index.possible(&bag), vec![1, 2, 5]);
+    let possible = games
+        .iter()
+        .filter(|g| g.is_possible(&bag))
+        .map(|g| g.id)
+        .collect::<Vec<_>>();
+    assert_eq!(index.possible(&bag), possible);
+    assert_eq!(
+        index.covering_bag(&[1, 2]).unwrap(),
+        "4 red, 3 green, 6 blue".parse().unwrap()
+    );
+    assert!(index.covering_bag(&[6]).is_err());
+
+    let frontier = index.pareto_frontier();
+    assert_eq!(
+        frontier,
+        vec![
+            ("2 blue, 3 green, 6 red".parse().unwrap(), vec![5]),
+            ("4 blue, 3 green, 1 red".parse().unwrap(), vec![2]),
+            ("6 blue, 2 green, 4 red".parse().unwrap(), vec![1]),
+        ]
+    );
+    assert_eq!(
+        index.query("possible 20 red, 13 green, 6 blue").unwrap(),
+        "[1, 2, 3, 5]"
+    );
+}
+
+#[test]
+fn round_trip_works() {
+    let printed = parse(EXAMPLE)
+        .unwrap()
+        .iter()
+        .map(|g| g.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_eq!(printed, EXAMPLE);
+}
+
+#[test]
+fn simulate_works() {
+    let bag = "3 cyan, 1 magenta, 7 yellow".parse::<Bag>().unwrap();
+    let games = simulate::simulate(&bag, 7, 50).unwrap();
+    assert_eq!(games.len(), 50);
+    assert_eq!(games, simulate::simulate(&bag, 7, 50).unwrap());
+    assert_ne!(games, simulate::simulate(&bag, 8, 50).unwrap());
+
+    for game in &games {
+        assert!(game.is_possible(&bag));
+        assert_eq!(&game.to_string().parse::<Game>().unwrap(), game);
+    }
+
+    assert!(simulate::simulate(&Bag::default(), 7, 1).is_err());
+}
+
+#[test]
+fn estimate_works() {
+    let game = "Game 1: 1 red, 1 blue".parse::<Game>().unwrap();
+    let estimates = estimate::estimate(&game, 3, 10);
+
+    // 1 red 1 blue can only ever give this frame, with a third cube it's 2 in 3
+    assert_eq!(estimates.len(), 3);
+    assert_eq!(estimates[0].bag, "1 red, 1 blue".parse().unwrap());
+    assert!(estimates[0].ln_likelihood.abs() < 1e-9);
+    assert_eq!(estimates[0].relative, 1.0);
+    for estimate in &estimates[1..] {
+        assert!((estimate.ln_likelihood - (2.0f64 / 3.0).ln()).abs() < 1e-9);
+        assert!((estimate.relative - 2.0 / 3.0).abs() < 1e-9);
+    }
+    assert_eq!(estimate::estimate(&game, 3, 1).len(), 1);
+
+    // with plenty of frames the most likely bag is the one they came from
+    let bag = "2 red, 6 blue".parse::<Bag>().unwrap();
+    let game = Game {
+        frames: simulate::simulate(&bag, 1, 100)
+            .unwrap()
+            .into_iter()
+            .flat_map(|g| g.frames)
+            .collect(),
+        id: 1,
+    };
+    assert!(game.frames.len() > 300);
+    let estimates = estimate::estimate(&game, 24, 20);
+    assert_eq!(estimates.len(), 20);
+    assert_eq!(estimates[0].bag, bag);
+    // the best bag is already only around 1e-100 likely in absolute terms
+    assert!(estimates[0].ln_likelihood < -200.0);
+    assert!(estimates
+        .windows(2)
+        .all(|w| w[0].ln_likelihood > w[1].ln_likelihood));
+    assert!(estimates[1..]
+        .iter()
+        .all(|e| e.relative > 0.0 && e.relative < 1.0));
+}
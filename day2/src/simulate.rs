@@ -0,0 +1,72 @@
+use anyhow::{anyhow, Result};
+
+use crate::{Bag, Frame, Game};
+
+const MAX_FRAMES: u64 = 6;
+
+// splitmix64, plenty for making up games and the same everywhere for a seed
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // somewhere in 0..n
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+// A handful of cubes is grabbed without looking, shown, and put back before
+// the next grab, so every frame draws from the full bag.
+fn draw(bag: &Bag, rng: &mut Rng) -> Frame {
+    let mut cubes = bag
+        .cubes
+        .iter()
+        .flat_map(|(color, num)| std::iter::repeat_n(color, *num))
+        .collect::<Vec<_>>();
+    let grab = 1 + rng.below(cubes.len() as u64) as usize;
+
+    // the first `grab` cubes of a partial shuffle
+    for i in 0..grab {
+        let j = i + rng.below((cubes.len() - i) as u64) as usize;
+        cubes.swap(i, j);
+    }
+
+    // colors are called out in the order they were pulled
+    let mut frame = Frame::default();
+    for color in &cubes[..grab] {
+        match frame.cubes.iter_mut().find(|(seen, _)| seen == *color) {
+            Some((_, num)) => *num += 1,
+            None => frame.cubes.push(((*color).clone(), 1)),
+        }
+    }
+
+    frame
+}
+
+pub fn simulate(bag: &Bag, seed: u64, games: usize) -> Result<Vec<Game>> {
+    if bag.cubes.values().sum::<usize>() == 0 {
+        return Err(anyhow!("can't draw from an empty bag"));
+    }
+
+    let mut rng = Rng::new(seed);
+    Ok((1..=games)
+        .map(|id| {
+            let frames = 1 + rng.below(MAX_FRAMES);
+            Game {
+                frames: (0..frames).map(|_| draw(bag, &mut rng)).collect(),
+                id,
+            }
+        })
+        .collect())
+}
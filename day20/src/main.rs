@@ -0,0 +1,300 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{anyhow, Context, Error, Result};
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+    println!("part1: {}", part1(input)?);
+    match part2(input)? {
+        Some(presses) => println!("part2: {}", presses),
+        None => println!("part2: nothing sends to rx"),
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Low,
+    High,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pulse<'a> {
+    from: &'a str,
+    to: &'a str,
+    level: Level,
+}
+
+#[derive(Debug)]
+enum Kind<'a> {
+    Broadcaster,
+    FlipFlop(bool),
+    Conjunction(HashMap<&'a str, Level>),
+}
+
+#[derive(Debug)]
+struct Module<'a> {
+    kind: Kind<'a>,
+    outputs: Vec<&'a str>,
+}
+
+impl<'a> Module<'a> {
+    fn receive(&mut self, pulse: &Pulse<'a>) -> Option<Level> {
+        match &mut self.kind {
+            Kind::Broadcaster => Some(pulse.level),
+            Kind::FlipFlop(on) => match pulse.level {
+                Level::High => None,
+                Level::Low => {
+                    *on = !*on;
+                    Some(if *on { Level::High } else { Level::Low })
+                }
+            },
+            Kind::Conjunction(memory) => {
+                memory.insert(pulse.from, pulse.level);
+                if memory.values().all(|level| level == &Level::High) {
+                    Some(Level::Low)
+                } else {
+                    Some(Level::High)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Network<'a> {
+    modules: HashMap<&'a str, Module<'a>>,
+}
+
+impl<'a> TryFrom<&'a str> for Network<'a> {
+    type Error = Error;
+
+    fn try_from(s: &'a str) -> std::result::Result<Self, Self::Error> {
+        // %a -> inv, con
+        let mut modules = s
+            .trim()
+            .lines()
+            .map(|line| {
+                let (name, outputs) = line
+                    .split_once(" -> ")
+                    .context(format!("couldn't get module outputs {}", line))?;
+                let outputs = outputs.split(", ").collect();
+                let (name, kind) = if let Some(name) = name.strip_prefix('%') {
+                    (name, Kind::FlipFlop(false))
+                } else if let Some(name) = name.strip_prefix('&') {
+                    (name, Kind::Conjunction(HashMap::new()))
+                } else if name == "broadcaster" {
+                    (name, Kind::Broadcaster)
+                } else {
+                    return Err(anyhow!("unknown module: {}", name));
+                };
+
+                Ok((name, Module { kind, outputs }))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        // conjunctions start out remembering a low pulse from every input
+        let links = modules
+            .iter()
+            .flat_map(|(name, module)| module.outputs.iter().map(move |output| (*name, *output)))
+            .collect::<Vec<_>>();
+        for (from, to) in links {
+            if let Some(Module {
+                kind: Kind::Conjunction(memory),
+                ..
+            }) = modules.get_mut(to)
+            {
+                memory.insert(from, Level::Low);
+            }
+        }
+
+        Ok(Self { modules })
+    }
+}
+
+impl<'a> Network<'a> {
+    fn push_button(&mut self, mut on_pulse: impl FnMut(&Pulse<'a>)) {
+        let mut queue = VecDeque::from([Pulse {
+            from: "button",
+            to: "broadcaster",
+            level: Level::Low,
+        }]);
+
+        while let Some(pulse) = queue.pop_front() {
+            on_pulse(&pulse);
+
+            let Some(module) = self.modules.get_mut(pulse.to) else {
+                continue; // untyped modules like output just swallow pulses
+            };
+            let Some(level) = module.receive(&pulse) else {
+                continue;
+            };
+
+            queue.extend(module.outputs.iter().map(|to| Pulse {
+                from: pulse.to,
+                to,
+                level,
+            }));
+        }
+    }
+
+    fn inputs(&self, name: &str) -> Vec<&'a str> {
+        self.modules
+            .iter()
+            .filter(|(_, module)| module.outputs.contains(&name))
+            .map(|(input, _)| *input)
+            .collect()
+    }
+}
+
+fn part1(input: &str) -> Result<usize> {
+    let mut network = Network::try_from(input)?;
+    let mut lows = 0;
+    let mut highs = 0;
+
+    for _ in 0..1000 {
+        network.push_button(|pulse| match pulse.level {
+            Level::Low => lows += 1,
+            Level::High => highs += 1,
+        });
+    }
+
+    Ok(lows * highs)
+}
+
+// the counters feeding rx are 12 bits wide, so their cycles are well under this
+const MAX_PRESSES: usize = 100_000;
+
+// None when the network has no rx to wait for
+fn part2(input: &str) -> Result<Option<usize>> {
+    let mut network = Network::try_from(input)?;
+
+    // rx is fed by a single conjunction, which only sends low once every one of
+    // its inputs has sent it a high in the same press. Each input cycles
+    // independently so rx goes low at the LCM of their periods.
+    let feeder = match network.inputs("rx")[..] {
+        [] => return Ok(None),
+        [feeder] => feeder,
+        _ => return Err(anyhow!("expected a single module feeding rx")),
+    };
+    match network.modules.get(feeder).map(|module| &module.kind) {
+        Some(Kind::Conjunction(_)) => {}
+        _ => return Err(anyhow!("module feeding rx isn't a conjunction")),
+    }
+
+    let mut cycles: HashMap<&str, usize> = network
+        .inputs(feeder)
+        .into_iter()
+        .map(|input| (input, 0))
+        .collect();
+    if cycles.is_empty() {
+        return Err(anyhow!("conjunction feeding rx has no inputs"));
+    }
+    let mut presses = 0;
+
+    while cycles.values().any(|cycle| *cycle == 0) {
+        if presses == MAX_PRESSES {
+            let mut silent = cycles
+                .iter()
+                .filter(|(_, cycle)| **cycle == 0)
+                .map(|(input, _)| *input)
+                .collect::<Vec<_>>();
+            silent.sort();
+            return Err(anyhow!(
+                "{:?} never sent {} a high in {} presses",
+                silent,
+                feeder,
+                MAX_PRESSES
+            ));
+        }
+        presses += 1;
+        network.push_button(|pulse| {
+            if pulse.to == feeder && pulse.level == Level::High {
+                if let Some(cycle) = cycles.get_mut(pulse.from) {
+                    if *cycle == 0 {
+                        *cycle = presses;
+                    }
+                }
+            }
+        });
+    }
+
+    Ok(Some(lcm(&cycles.into_values().collect::<Vec<_>>())))
+}
+
+pub fn lcm(nums: &[usize]) -> usize {
+    let Some((&a, rest)) = nums.split_first() else {
+        return 1;
+    };
+    if rest.is_empty() {
+        return a;
+    }
+    let b = lcm(rest);
+    a * b / gcd_of_two_numbers(a, b)
+}
+
+fn gcd_of_two_numbers(a: usize, b: usize) -> usize {
+    if b == 0 {
+        return a;
+    }
+    gcd_of_two_numbers(b, a % b)
+}
+
+#[test]
+fn part1_works() {
+    let input = r#"broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a"#;
+    assert_eq!(part1(input).unwrap(), 32000000);
+}
+
+#[test]
+fn part1_interesting_works() {
+    let input = r#"broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output"#;
+    assert_eq!(part1(input).unwrap(), 11687500);
+}
+
+#[test]
+fn part2_works() {
+    // two flip-flop counters, one of period 4 and one of period 8, each
+    // inverted into the conjunction feeding rx
+    let input = r#"broadcaster -> a, c
+%a -> b
+%b -> inv1
+%c -> d
+%d -> e
+%e -> inv2
+&inv1 -> hub
+&inv2 -> hub
+&hub -> rx"#;
+    assert_eq!(part2(input).unwrap(), Some(8));
+
+    assert_eq!(part2("broadcaster -> a\n%a -> b").unwrap(), None);
+    assert!(part2("broadcaster -> a\n%a -> b\n&hub -> rx").is_err());
+
+    // f never gets a pulse so hub would wait on it forever
+    let error = part2("broadcaster -> a\n%a -> hub\n%f -> hub\n&hub -> rx").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "[\"f\"] never sent hub a high in 100000 presses"
+    );
+    assert_eq!(lcm(&[]), 1);
+    assert_eq!(lcm(&[4, 6]), 12);
+
+    let mut network = Network::try_from(input).unwrap();
+    let mut first_low = None;
+    for press in 1..=8 {
+        network.push_button(|pulse| {
+            if pulse.to == "rx" && pulse.level == Level::Low && first_low.is_none() {
+                first_low = Some(press);
+            }
+        });
+    }
+    assert_eq!(first_low, Some(8));
+}
@@ -0,0 +1,235 @@
+use std::{collections::HashSet, str::FromStr};
+
+use anyhow::{anyhow, Context, Error, Result};
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+    println!("part1: {}", part1(input, 64)?);
+    println!("part2: {}", part2(input, 26501365)?);
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
+struct Coord(isize, isize);
+
+impl Coord {
+    const OFFSETS: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+    fn neighbors(self) -> impl Iterator<Item = Coord> {
+        Self::OFFSETS
+            .into_iter()
+            .map(move |(x, y)| Coord(self.0 + x, self.1 + y))
+    }
+}
+
+struct Map {
+    rocks: Vec<Vec<bool>>,
+    width: isize,
+    height: isize,
+    start: Coord,
+}
+
+impl FromStr for Map {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut start = None;
+        let rocks = s
+            .trim()
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| match c {
+                        '#' => Ok(true),
+                        '.' => Ok(false),
+                        'S' => {
+                            start = Some(Coord(x as isize, y as isize));
+                            Ok(false)
+                        }
+                        _ => Err(anyhow!("unknown tile: {}", c)),
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let height = rocks.len() as isize;
+        let width = rocks.first().context("couldn't get width")?.len() as isize;
+
+        Ok(Self {
+            rocks,
+            width,
+            height,
+            start: start.context("couldn't find start")?,
+        })
+    }
+}
+
+impl Map {
+    fn is_garden(&self, Coord(x, y): Coord, infinite: bool) -> bool {
+        if !infinite && (x < 0 || y < 0 || x >= self.width || y >= self.height) {
+            return false;
+        }
+
+        !self.rocks[y.rem_euclid(self.height) as usize][x.rem_euclid(self.width) as usize]
+    }
+
+    fn layers(&self, infinite: bool) -> Layers<'_> {
+        Layers {
+            map: self,
+            infinite,
+            seen: HashSet::from([self.start]),
+            frontier: vec![self.start],
+        }
+    }
+
+    // a plot can be stood on at exactly `steps` if it's reachable in fewer
+    // with the same parity, just step back and forth
+    fn reachable(&self, steps: usize, infinite: bool) -> usize {
+        self.layers(infinite)
+            .take(steps + 1)
+            .enumerate()
+            .filter(|(distance, _)| distance % 2 == steps % 2)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    // Once the frontier has spread past the first few tiles every extra map
+    // width of steps adds another ring of tiles, so counts sampled a whole
+    // map apart grow quadratically. Keep sampling until the second difference
+    // settles, then extend the curve out to `steps`.
+    fn extrapolate(&self, steps: usize) -> Result<usize> {
+        if self.width != self.height {
+            return Err(anyhow!("extrapolation needs a square map"));
+        }
+        let size = self.width as usize;
+        let remainder = steps % size;
+        let mut totals = [0, 0];
+        let mut samples: Vec<i128> = vec![];
+
+        for (distance, count) in self.layers(true).enumerate() {
+            totals[distance % 2] += count;
+            if distance == steps {
+                return Ok(totals[distance % 2]);
+            }
+            if distance < remainder || !(distance - remainder).is_multiple_of(size) {
+                continue;
+            }
+
+            samples.push(totals[distance % 2] as i128);
+            let [.., y0, y1, y2, y3] = samples[..] else {
+                continue;
+            };
+            let second = y3 - 2 * y2 + y1;
+            if second != y2 - 2 * y1 + y0 {
+                continue;
+            }
+
+            let n = ((steps - distance) / size) as i128;
+            let first = y3 - y2;
+            let total = y3 + n * first + n * (n + 1) / 2 * second;
+            return usize::try_from(total).context("extrapolated count out of range");
+        }
+
+        Err(anyhow!("ran out of garden before {} steps", steps))
+    }
+}
+
+struct Layers<'a> {
+    map: &'a Map,
+    infinite: bool,
+    seen: HashSet<Coord>,
+    frontier: Vec<Coord>,
+}
+
+// number of plots first reached at each distance from the start
+impl Iterator for Layers<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.frontier.is_empty() {
+            return None;
+        }
+
+        let count = self.frontier.len();
+        let mut next = vec![];
+        for coord in &self.frontier {
+            for neighbor in coord.neighbors() {
+                if self.map.is_garden(neighbor, self.infinite) && self.seen.insert(neighbor) {
+                    next.push(neighbor);
+                }
+            }
+        }
+        self.frontier = next;
+
+        Some(count)
+    }
+}
+
+fn part1(input: &str, steps: usize) -> Result<usize> {
+    let map = input.parse::<Map>()?;
+    Ok(map.reachable(steps, false))
+}
+
+fn part2(input: &str, steps: usize) -> Result<usize> {
+    let map = input.parse::<Map>()?;
+    map.extrapolate(steps)
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = r#"...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+..........."#;
+
+#[test]
+fn part1_works() {
+    assert_eq!(part1(EXAMPLE, 6).unwrap(), 16);
+}
+
+#[test]
+fn infinite_works() {
+    let map = EXAMPLE.parse::<Map>().unwrap();
+    assert_eq!(map.reachable(6, true), 16);
+    assert_eq!(map.reachable(10, true), 50);
+    assert_eq!(map.reachable(50, true), 1594);
+    assert_eq!(map.reachable(100, true), 6536);
+}
+
+#[test]
+fn part2_works() {
+    assert_eq!(part2(EXAMPLE, 500).unwrap(), 167004);
+    assert_eq!(part2(EXAMPLE, 1000).unwrap(), 668697);
+    assert_eq!(part2(EXAMPLE, 5000).unwrap(), 16733044);
+}
+
+#[test]
+fn extrapolate_matches_brute_force() {
+    // like the real input, the start row and column and the border are clear
+    let input = r#".........
+.#..#..#.
+...#.#...
+.#.....#.
+....S....
+..#...#..
+.#..#.#..
+...#...#.
+........."#;
+    let map = input.parse::<Map>().unwrap();
+
+    for steps in [4, 4 + 9 * 5, 4 + 9 * 8, 4 + 9 * 11, 3 + 9 * 7, 100] {
+        assert_eq!(
+            map.extrapolate(steps).unwrap(),
+            map.reachable(steps, true),
+            "steps {steps}"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::{Context, Error, Result};
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+    println!("part1: {}", part1(input)?);
+    println!("part2: {}", part2(input)?);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Point {
+    x: usize,
+    y: usize,
+    z: usize,
+}
+
+impl FromStr for Point {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut nums = s.split(',').map(|num| {
+            num.parse::<usize>()
+                .context(format!("couldn't parse num {}", num))
+        });
+
+        Ok(Self {
+            x: nums.next().context("couldn't get x")??,
+            y: nums.next().context("couldn't get y")??,
+            z: nums.next().context("couldn't get z")??,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Brick {
+    start: Point,
+    end: Point,
+}
+
+impl FromStr for Brick {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // 1,0,1~1,2,1
+        let (start, end) = s.split_once('~').context("couldn't split brick")?;
+        let start = start.parse::<Point>()?;
+        let end = end.parse::<Point>()?;
+
+        Ok(Self {
+            start: Point {
+                x: start.x.min(end.x),
+                y: start.y.min(end.y),
+                z: start.z.min(end.z),
+            },
+            end: Point {
+                x: start.x.max(end.x),
+                y: start.y.max(end.y),
+                z: start.z.max(end.z),
+            },
+        })
+    }
+}
+
+impl Brick {
+    fn footprint(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.start.x..=self.end.x)
+            .flat_map(move |x| (self.start.y..=self.end.y).map(move |y| (x, y)))
+    }
+
+    fn height(&self) -> usize {
+        self.end.z - self.start.z + 1
+    }
+}
+
+// None is the ground, which holds everything up
+type Support = Option<usize>;
+
+struct Stack {
+    // settled bricks in landing order, so supporters always come first
+    bricks: Vec<Brick>,
+    supported_by: Vec<Vec<Support>>,
+    supports: Vec<Vec<usize>>,
+}
+
+impl FromStr for Stack {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut bricks = s
+            .trim()
+            .lines()
+            .map(|line| line.parse::<Brick>())
+            .collect::<Result<Vec<_>>>()?;
+        bricks.sort_by_key(|brick| brick.start.z);
+
+        // top of the pile at each column and which brick it belongs to
+        let mut heights: HashMap<(usize, usize), (usize, Support)> = HashMap::new();
+        let mut supported_by = vec![];
+        let mut supports = vec![vec![]; bricks.len()];
+
+        for (id, brick) in bricks.iter_mut().enumerate() {
+            let top = brick
+                .footprint()
+                .map(|column| heights.get(&column).map_or(0, |(z, _)| *z))
+                .max()
+                .unwrap_or(0);
+
+            let mut below = brick
+                .footprint()
+                .filter_map(|column| heights.get(&column))
+                .filter(|(z, _)| *z == top)
+                .map(|(_, support)| *support)
+                .collect::<Vec<_>>();
+            below.sort();
+            below.dedup();
+            if below.is_empty() {
+                below.push(None);
+            }
+
+            for support in below.iter().flatten() {
+                supports[*support].push(id);
+            }
+            supported_by.push(below);
+
+            let height = brick.height();
+            brick.start.z = top + 1;
+            brick.end.z = top + height;
+            for column in brick.footprint() {
+                heights.insert(column, (brick.end.z, Some(id)));
+            }
+        }
+
+        Ok(Self {
+            bricks,
+            supported_by,
+            supports,
+        })
+    }
+}
+
+impl Stack {
+    fn can_remove(&self, id: usize) -> bool {
+        self.supports[id]
+            .iter()
+            .all(|above| self.supported_by[*above].len() > 1)
+    }
+
+    // A brick falls when `id` is removed exactly when every path from the
+    // ground up to it passes through `id`, i.e. `id` dominates it. Bricks are
+    // in landing order, so each brick's immediate dominator is the common
+    // ancestor of its supporters in the tree built so far.
+    fn chain_reactions(&self) -> Vec<usize> {
+        let mut dominator: Vec<Support> = Vec::with_capacity(self.bricks.len());
+        let mut depth: Vec<usize> = Vec::with_capacity(self.bricks.len());
+        let depth_of = |depth: &[usize], support: Support| support.map_or(0, |id| depth[id]);
+
+        for supporters in &self.supported_by {
+            let mut common = supporters[0];
+            for &other in &supporters[1..] {
+                let mut other = other;
+                while common != other {
+                    if depth_of(&depth, common) >= depth_of(&depth, other) {
+                        common = common.and_then(|id| dominator[id]);
+                    } else {
+                        other = other.and_then(|id| dominator[id]);
+                    }
+                }
+            }
+
+            dominator.push(common);
+            depth.push(depth_of(&depth, common) + 1);
+        }
+
+        // everything a brick dominates falls with it
+        let mut falls = vec![0; self.bricks.len()];
+        for id in (0..self.bricks.len()).rev() {
+            if let Some(parent) = dominator[id] {
+                falls[parent] += falls[id] + 1;
+            }
+        }
+
+        falls
+    }
+}
+
+fn part1(input: &str) -> Result<usize> {
+    let stack = input.parse::<Stack>()?;
+    Ok((0..stack.bricks.len())
+        .filter(|id| stack.can_remove(*id))
+        .count())
+}
+
+fn part2(input: &str) -> Result<usize> {
+    let stack = input.parse::<Stack>()?;
+    Ok(stack.chain_reactions().into_iter().sum())
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = r#"1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9"#;
+
+#[test]
+fn part1_works() {
+    assert_eq!(part1(EXAMPLE).unwrap(), 5);
+}
+
+#[test]
+fn part2_works() {
+    assert_eq!(part2(EXAMPLE).unwrap(), 7);
+    let stack = EXAMPLE.parse::<Stack>().unwrap();
+    assert_eq!(stack.chain_reactions(), vec![6, 0, 0, 0, 0, 1, 0]);
+}
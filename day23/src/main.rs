@@ -0,0 +1,258 @@
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::{anyhow, Context, Error, Result};
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+    println!("part1: {}", part1(input)?);
+    println!("part2: {}", part2(input)?);
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
+struct Coord(usize, usize);
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+enum Dir {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Dir {
+    const ALL: [Dir; 4] = [Dir::North, Dir::South, Dir::East, Dir::West];
+}
+
+impl Coord {
+    fn step(self, dir: Dir) -> Option<Coord> {
+        let Coord(x, y) = self;
+        Some(match dir {
+            Dir::North => Coord(x, y.checked_sub(1)?),
+            Dir::South => Coord(x, y + 1),
+            Dir::East => Coord(x + 1, y),
+            Dir::West => Coord(x.checked_sub(1)?, y),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Tile {
+    Path,
+    Forest,
+    Slope(Dir),
+}
+
+impl TryFrom<char> for Tile {
+    type Error = Error;
+
+    fn try_from(value: char) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
+            '.' => Tile::Path,
+            '#' => Tile::Forest,
+            '^' => Tile::Slope(Dir::North),
+            'v' => Tile::Slope(Dir::South),
+            '>' => Tile::Slope(Dir::East),
+            '<' => Tile::Slope(Dir::West),
+            _ => return Err(anyhow!("unknown tile: {}", value)),
+        })
+    }
+}
+
+struct Map {
+    tiles: Vec<Vec<Tile>>,
+    start: Coord,
+    end: Coord,
+}
+
+impl FromStr for Map {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let tiles = s
+            .trim()
+            .lines()
+            .map(|line| line.chars().map(Tile::try_from).collect::<Result<Vec<_>>>())
+            .collect::<Result<Vec<_>>>()?;
+
+        fn gap(row: Option<&Vec<Tile>>) -> Option<usize> {
+            row?.iter().position(|tile| tile == &Tile::Path)
+        }
+        let start = gap(tiles.first()).context("couldn't find start")?;
+        let end = gap(tiles.last()).context("couldn't find end")?;
+        let height = tiles.len();
+
+        Ok(Self {
+            tiles,
+            start: Coord(start, 0),
+            end: Coord(end, height - 1),
+        })
+    }
+}
+
+impl Map {
+    fn get(&self, Coord(x, y): Coord) -> Option<Tile> {
+        self.tiles.get(y)?.get(x).copied()
+    }
+
+    fn moves(&self, coord: Coord, slippery: bool) -> impl Iterator<Item = Coord> + '_ {
+        let tile = self.get(coord);
+        Dir::ALL.into_iter().filter_map(move |dir| {
+            if let (true, Some(Tile::Slope(slope))) = (slippery, tile) {
+                if slope != dir {
+                    return None;
+                }
+            }
+            let next = coord.step(dir)?;
+            (self.get(next)? != Tile::Forest).then_some(next)
+        })
+    }
+
+    fn is_junction(&self, coord: Coord) -> bool {
+        coord == self.start || coord == self.end || self.moves(coord, false).count() > 2
+    }
+
+    // Collapse the corridors between junctions into weighted edges. The start
+    // is always node 0 and the end node 1.
+    fn graph(&self, slippery: bool) -> Result<Graph> {
+        let mut junctions = vec![self.start, self.end];
+        for (y, row) in self.tiles.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                let coord = Coord(x, y);
+                if tile != &Tile::Forest && !junctions.contains(&coord) && self.is_junction(coord) {
+                    junctions.push(coord);
+                }
+            }
+        }
+        if junctions.len() > 64 {
+            return Err(anyhow!("too many junctions for a u64 mask"));
+        }
+
+        let index: HashMap<Coord, usize> = junctions
+            .iter()
+            .enumerate()
+            .map(|(i, coord)| (*coord, i))
+            .collect();
+        let mut edges = vec![vec![]; junctions.len()];
+
+        for (from, junction) in junctions.iter().enumerate() {
+            for first in self.moves(*junction, slippery) {
+                let mut previous = *junction;
+                let mut head = first;
+                let mut length = 1;
+
+                // corridors have no choices so just keep going until a junction
+                // or a slope turns us back
+                while !index.contains_key(&head) {
+                    let Some(next) = self.moves(head, slippery).find(|next| *next != previous)
+                    else {
+                        break;
+                    };
+                    previous = head;
+                    head = next;
+                    length += 1;
+                }
+
+                if let Some(to) = index.get(&head) {
+                    if *to != from {
+                        edges[from].push((*to, length));
+                    }
+                }
+            }
+        }
+
+        Ok(Graph { edges })
+    }
+}
+
+struct Graph {
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+impl Graph {
+    const START: usize = 0;
+    const END: usize = 1;
+
+    fn longest_path(&self) -> Option<usize> {
+        // the last junction before the end has to go straight there, any other
+        // choice walls the end off
+        let last = match self
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edges)| edges.iter().any(|(to, _)| *to == Self::END))
+            .map(|(from, _)| from)
+            .collect::<Vec<_>>()[..]
+        {
+            [last] => Some(last),
+            _ => None,
+        };
+
+        self.search(Self::START, 1 << Self::START, last)
+    }
+
+    fn search(&self, node: usize, visited: u64, last: Option<usize>) -> Option<usize> {
+        if node == Self::END {
+            return Some(0);
+        }
+
+        self.edges[node]
+            .iter()
+            .filter(|(to, _)| visited & (1 << to) == 0)
+            .filter(|(to, _)| Some(node) != last || *to == Self::END)
+            .filter_map(|(to, length)| {
+                self.search(*to, visited | (1 << to), last)
+                    .map(|rest| rest + length)
+            })
+            .max()
+    }
+}
+
+fn part1(input: &str) -> Result<usize> {
+    let map = input.parse::<Map>()?;
+    map.graph(true)?
+        .longest_path()
+        .context("couldn't reach the end")
+}
+
+fn part2(input: &str) -> Result<usize> {
+    let map = input.parse::<Map>()?;
+    map.graph(false)?
+        .longest_path()
+        .context("couldn't reach the end")
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = r#"#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#"#;
+
+#[test]
+fn part1_works() {
+    assert_eq!(part1(EXAMPLE).unwrap(), 94);
+}
+
+#[test]
+fn part2_works() {
+    assert_eq!(part2(EXAMPLE).unwrap(), 154);
+}
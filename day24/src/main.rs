@@ -0,0 +1,262 @@
+use std::{
+    ops::{Add, Mul, Sub},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Context, Error, Result};
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+    println!("part1: {}", part1(input, 200000000000000, 400000000000000)?);
+    println!("part2: {}", part2(input)?);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Vec3 {
+    x: i128,
+    y: i128,
+    z: i128,
+}
+
+impl FromStr for Vec3 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut nums = s.split(',').map(|num| {
+            num.trim()
+                .parse::<i128>()
+                .context(format!("couldn't parse num {}", num))
+        });
+
+        Ok(Self {
+            x: nums.next().context("couldn't get x")??,
+            y: nums.next().context("couldn't get y")??,
+            z: nums.next().context("couldn't get z")??,
+        })
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Vec3 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec3 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl Mul<i128> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: i128) -> Self::Output {
+        Vec3 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
+
+impl Vec3 {
+    fn cross(self, rhs: Self) -> Vec3 {
+        Vec3 {
+            x: self.y * rhs.z - self.z * rhs.y,
+            y: self.z * rhs.x - self.x * rhs.z,
+            z: self.x * rhs.y - self.y * rhs.x,
+        }
+    }
+
+    // only the direction matters for normals, so keep the numbers small
+    fn reduce(self) -> Vec3 {
+        let divisor = gcd(gcd(self.x, self.y), self.z);
+        if divisor == 0 {
+            return self;
+        }
+        Vec3 {
+            x: self.x / divisor,
+            y: self.y / divisor,
+            z: self.z / divisor,
+        }
+    }
+
+    fn checked_div(self, rhs: i128) -> Option<Vec3> {
+        if rhs == 0 || self.x % rhs != 0 || self.y % rhs != 0 || self.z % rhs != 0 {
+            return None;
+        }
+        Some(Vec3 {
+            x: self.x / rhs,
+            y: self.y / rhs,
+            z: self.z / rhs,
+        })
+    }
+
+    fn components(self) -> [i128; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        return a.abs();
+    }
+    gcd(b, a % b)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Hailstone {
+    position: Vec3,
+    velocity: Vec3,
+}
+
+impl FromStr for Hailstone {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // 19, 13, 30 @ -2,  1, -2
+        let (position, velocity) = s.split_once(" @ ").context("couldn't split hailstone")?;
+
+        Ok(Self {
+            position: position.parse()?,
+            velocity: velocity.parse()?,
+        })
+    }
+}
+
+impl Hailstone {
+    // Solve p1 + t * v1 = p2 + s * v2 in x and y with Cramer's rule, keeping
+    // everything over the shared determinant so no division is needed.
+    fn crosses_within(&self, other: &Hailstone, min: i128, max: i128) -> bool {
+        let (p1, v1) = (self.position, self.velocity);
+        let (p2, v2) = (other.position, other.velocity);
+        let d = p2 - p1;
+
+        let mut det = v2.x * v1.y - v1.x * v2.y;
+        if det == 0 {
+            return false; // parallel
+        }
+        let mut t = v2.x * d.y - v2.y * d.x;
+        let mut s = v1.x * d.y - v1.y * d.x;
+        if det < 0 {
+            det = -det;
+            t = -t;
+            s = -s;
+        }
+
+        if t < 0 || s < 0 {
+            return false; // crossed in the past
+        }
+
+        let x = p1.x * det + t * v1.x;
+        let y = p1.y * det + t * v1.y;
+        let range = min * det..=max * det;
+        range.contains(&x) && range.contains(&y)
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Hailstone>> {
+    input
+        .trim()
+        .lines()
+        .map(|line| line.parse::<Hailstone>())
+        .collect()
+}
+
+// Working relative to one hailstone it sits still at the origin, so the rock's
+// line passes through the origin and lies in the plane through the origin and
+// each other hailstone's line. Two such planes meet along the rock's
+// direction, which pins down when it hits each of them.
+fn throw(reference: &Hailstone, a: &Hailstone, b: &Hailstone) -> Option<Vec3> {
+    let relative = |hailstone: &Hailstone| {
+        (
+            hailstone.position - reference.position,
+            hailstone.velocity - reference.velocity,
+        )
+    };
+    let (qa, wa) = relative(a);
+    let (qb, wb) = relative(b);
+
+    let normal_a = qa.cross(wa).reduce();
+    let normal_b = qb.cross(wb).reduce();
+    let direction = normal_a.cross(normal_b).reduce();
+    if direction == Vec3::default() {
+        return None;
+    }
+
+    // (q + w * t) x direction = 0
+    let hit_time = |q: Vec3, w: Vec3| {
+        let lhs = q.cross(direction).components();
+        let rhs = w.cross(direction).components();
+        (0..3)
+            .find(|i| rhs[*i] != 0)
+            .filter(|i| lhs[*i] % rhs[*i] == 0)
+            .map(|i| -lhs[i] / rhs[i])
+    };
+    let ta = hit_time(qa, wa)?;
+    let tb = hit_time(qb, wb)?;
+
+    let hit_a = a.position + a.velocity * ta;
+    let hit_b = b.position + b.velocity * tb;
+    let velocity = (hit_b - hit_a).checked_div(tb - ta)?;
+
+    Some(hit_a - velocity * ta)
+}
+
+fn part1(input: &str, min: i128, max: i128) -> Result<usize> {
+    let hailstones = parse(input)?;
+
+    Ok(hailstones
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| hailstones[i + 1..].iter().map(move |b| (a, b)))
+        .filter(|(a, b)| a.crosses_within(b, min, max))
+        .count())
+}
+
+fn part2(input: &str) -> Result<i128> {
+    let hailstones = parse(input)?;
+    let (reference, rest) = hailstones.split_first().context("no hailstones")?;
+
+    // some pairs can be degenerate (parallel, or in the same plane) so keep
+    // trying until one pins the rock down
+    let position = rest
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| rest[i + 1..].iter().map(move |b| (a, b)))
+        .find_map(|(a, b)| throw(reference, a, b))
+        .ok_or_else(|| anyhow!("couldn't find a throw that hits every hailstone"))?;
+
+    Ok(position.x + position.y + position.z)
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = r#"19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3"#;
+
+#[test]
+fn part1_works() {
+    assert_eq!(part1(EXAMPLE, 7, 27).unwrap(), 2);
+}
+
+#[test]
+fn part2_works() {
+    assert_eq!(part2(EXAMPLE).unwrap(), 47);
+}
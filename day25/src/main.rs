@@ -0,0 +1,175 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{anyhow, Context, Error, Result};
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+    println!("part1: {}", part1(input)?);
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Graph<'a> {
+    names: Vec<&'a str>,
+    edges: Vec<Vec<usize>>,
+}
+
+impl<'a> TryFrom<&'a str> for Graph<'a> {
+    type Error = Error;
+
+    fn try_from(s: &'a str) -> std::result::Result<Self, Self::Error> {
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut names = vec![];
+        let mut edges: Vec<Vec<usize>> = vec![];
+        let mut node = |name: &'a str| {
+            *index.entry(name).or_insert_with(|| {
+                names.push(name);
+                edges.push(vec![]);
+                names.len() - 1
+            })
+        };
+
+        // jqt: rhn xhk nvd
+        let mut links = vec![];
+        for line in s.trim().lines() {
+            let (from, tos) = line
+                .split_once(": ")
+                .context(format!("couldn't get wires {}", line))?;
+            let from = node(from);
+            for to in tos.split_whitespace() {
+                links.push((from, node(to)));
+            }
+        }
+
+        for (from, to) in links {
+            edges[from].push(to);
+            edges[to].push(from);
+        }
+
+        Ok(Self { names, edges })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Cut {
+    size: usize,
+    // which nodes stay connected to the source once the cut is made
+    side: Vec<bool>,
+}
+
+impl Cut {
+    fn group_sizes(&self) -> (usize, usize) {
+        let source_side = self.side.iter().filter(|side| **side).count();
+        (source_side, self.side.len() - source_side)
+    }
+}
+
+impl Graph<'_> {
+    // Edmonds–Karp with every wire as capacity one in both directions. Gives
+    // up with None once the flow reaches `limit`, as any cut found would be no
+    // smaller than one we already have.
+    pub fn min_cut(&self, source: usize, sink: usize, limit: usize) -> Option<Cut> {
+        let mut flow: HashMap<(usize, usize), i32> = HashMap::new();
+        let residual =
+            |flow: &HashMap<(usize, usize), i32>, from, to| 1 - flow.get(&(from, to)).unwrap_or(&0);
+        let mut size = 0;
+
+        loop {
+            let mut previous: Vec<Option<usize>> = vec![None; self.names.len()];
+            previous[source] = Some(source);
+            let mut queue = VecDeque::from([source]);
+
+            while let Some(node) = queue.pop_front() {
+                if node == sink {
+                    break;
+                }
+                for &next in &self.edges[node] {
+                    if previous[next].is_none() && residual(&flow, node, next) > 0 {
+                        previous[next] = Some(node);
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            if previous[sink].is_none() {
+                // whatever the source can still reach is its side of the cut
+                let side = previous.iter().map(Option::is_some).collect();
+                return Some(Cut { size, side });
+            }
+
+            size += 1;
+            if size >= limit {
+                return None;
+            }
+
+            let mut node = sink;
+            while node != source {
+                let from = previous[node]?;
+                *flow.entry((from, node)).or_insert(0) += 1;
+                *flow.entry((node, from)).or_insert(0) -= 1;
+                node = from;
+            }
+        }
+    }
+
+    // Every cut separates node 0 from something, so the smallest of the
+    // 0-to-t cuts is the global minimum.
+    pub fn global_min_cut(&self) -> Option<Cut> {
+        let mut best: Option<Cut> = None;
+
+        for sink in 1..self.names.len() {
+            let limit = best.as_ref().map_or(usize::MAX, |cut| cut.size);
+            if let Some(cut) = self.min_cut(0, sink, limit) {
+                best = Some(cut);
+            }
+        }
+
+        best
+    }
+}
+
+fn part1(input: &str) -> Result<usize> {
+    let graph = Graph::try_from(input)?;
+    let cut = graph.global_min_cut().context("couldn't cut the graph")?;
+    if cut.size != 3 {
+        return Err(anyhow!("expected to cut 3 wires, needed {}", cut.size));
+    }
+
+    let (a, b) = cut.group_sizes();
+    Ok(a * b)
+}
+
+#[test]
+fn part1_works() {
+    let input = r#"jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr"#;
+    assert_eq!(part1(input).unwrap(), 54);
+}
+
+#[test]
+fn min_cut_works() {
+    // two triangles joined by a single wire
+    let input = r#"a: b c
+b: c
+c: d
+d: e f
+e: f"#;
+    let graph = Graph::try_from(input).unwrap();
+    let cut = graph.global_min_cut().unwrap();
+    assert_eq!(cut.size, 1);
+    assert_eq!(cut.group_sizes(), (3, 3));
+
+    let cut = graph.min_cut(0, 1, usize::MAX).unwrap();
+    assert_eq!(cut.size, 2);
+}
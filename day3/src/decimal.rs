@@ -0,0 +1,69 @@
+use std::{
+    fmt::Display,
+    iter::{Product, Sum},
+    ops::{Add, Mul},
+};
+
+// An exact decimal, `digits` over 10^`scale`, so sums and products of the
+// numbers in a schematic come out with the digits they were written with
+// rather than whatever a float rounds them to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decimal {
+    pub digits: i128,
+    pub scale: u32,
+}
+
+impl Decimal {
+    pub fn new(digits: i128, scale: u32) -> Self {
+        Self { digits, scale }
+    }
+
+    fn rescale(self, scale: u32) -> i128 {
+        self.digits * 10i128.pow(scale - self.scale)
+    }
+}
+
+impl Add for Decimal {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let scale = self.scale.max(other.scale);
+        Self::new(self.rescale(scale) + other.rescale(scale), scale)
+    }
+}
+
+impl Mul for Decimal {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(self.digits * other.digits, self.scale + other.scale)
+    }
+}
+
+impl Sum for Decimal {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(0, 0), Add::add)
+    }
+}
+
+impl Product for Decimal {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(1, 0), Mul::mul)
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.digits < 0 { "-" } else { "" };
+        let digits = self.digits.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return f.pad(&format!("{}{}", sign, digits));
+        }
+
+        // enough leading zeros for there to be something before the point
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        f.pad(&format!("{}{}.{}", sign, whole, fraction))
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt::Write;
+
+use crate::Schematic;
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Numbers and symbols as nodes pinned at their place in the grid (for
+// `neato -n`), with an edge for every touch. Symbols matching the gear rule
+// are filled in and numbers touching nothing are dashed.
+pub fn dot(schematic: &Schematic, gear: char, count: usize) -> String {
+    let mut out = String::from("graph schematic {\n    node [fontname=monospace];\n");
+
+    for (number, edges) in schematic.numbers.iter().zip(&schematic.number_edges) {
+        let style = if edges.is_empty() {
+            ", style=dashed, color=gray"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "    n{} [label=\"{}\\n({}, {})\", pos=\"{},{}\"{}];",
+            number.id,
+            number.value(),
+            number.x_start,
+            number.y,
+            number.x_start * 72,
+            number.y as isize * -72,
+            style
+        );
+    }
+
+    let gears = schematic
+        .gears(gear, count)
+        .iter()
+        .map(|(symbol, _)| symbol.id)
+        .collect::<Vec<_>>();
+    for symbol in &schematic.symbols {
+        let style = if gears.contains(&symbol.id) {
+            ", style=filled, fillcolor=gold"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "    s{} [label=\"{}\\n({}, {})\", shape=box, pos=\"{},{}\"{}];",
+            symbol.id,
+            escape(&symbol.kind.to_string()),
+            symbol.x,
+            symbol.y,
+            symbol.x * 72,
+            symbol.y as isize * -72,
+            style
+        );
+    }
+
+    for (number, edges) in schematic.number_edges.iter().enumerate() {
+        for symbol in edges {
+            let _ = writeln!(out, "    n{} -- s{};", number, symbol);
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
@@ -1,5 +1,9 @@
-use anyhow::{Context, Error, Result};
-use std::{collections::BTreeMap, ops::Range, rc::Rc, str::FromStr};
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, BTreeSet};
+
+mod decimal;
+mod dot;
+use decimal::Decimal;
 
 #[derive(Copy, Clone)]
 struct Coord(usize, usize);
@@ -35,16 +39,25 @@ enum Tile {
 
 #[derive(Debug, Eq, PartialEq)]
 struct PartNumber {
+    // position in `Schematic::numbers`, so equal numbers stay apart
+    id: usize,
+    // every digit run together, leaving the sign and point to the fields below
     number: usize,
+    negative: bool,
+    // digits after the decimal point, if there is one
+    point: Option<u32>,
     y: usize,
     x_start: usize,
     x_end: usize,
 }
 
 impl PartNumber {
-    fn new(x: usize, y: usize, number: usize) -> Self {
+    fn new(id: usize, x: usize, y: usize, number: usize) -> Self {
         Self {
+            id,
             number,
+            negative: false,
+            point: None,
             y,
             x_start: x,
             x_end: x,
@@ -52,18 +65,28 @@ impl PartNumber {
     }
 
     fn add_onto(&mut self, x: usize, num: usize) {
-        self.number = self.number * 10 + num as usize;
+        self.number = self.number * 10 + num;
+        if let Some(decimals) = &mut self.point {
+            *decimals += 1;
+        }
         self.x_end = x;
     }
 
-    fn symbol_close(&self, map: &Map) -> bool {
-        fn find_in_map(map: &Map, x: usize, y: usize) -> bool {
-            Coord(x, y)
-                .surrounding()
-                .any(|coord| map.contains_key(&coord))
-        }
+    fn add_point(&mut self, x: usize) {
+        self.point = Some(0);
+        self.x_end = x;
+    }
+
+    fn value(&self) -> Decimal {
+        let digits = self.number as i128;
+        Decimal::new(
+            if self.negative { -digits } else { digits },
+            self.point.unwrap_or(0),
+        )
+    }
 
-        (self.x_start..=self.x_end).any(|x| find_in_map(&map, x, self.y))
+    fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.x_start..=self.x_end).map(|x| (x, self.y))
     }
 }
 
@@ -77,117 +100,228 @@ impl From<char> for Tile {
     }
 }
 
-type Map = BTreeMap<(usize, usize), Tile>;
+// How numbers are read out of the grid. The default is the puzzle's, where
+// `-` and `.` are never part of a number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Lexing {
+    // a `-` right before a digit makes the number negative
+    signed: bool,
+    // a `.` between digits is a decimal point rather than a blank
+    decimals: bool,
+    // whether a sign still counts as a `-` symbol next to its number
+    sign_is_symbol: bool,
+}
 
-fn part1(input: &str) -> usize {
-    let mut numbers = Vec::new();
-    let mut symbol_map = Map::new();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Symbol {
+    // position in `Schematic::symbols`
+    id: usize,
+    kind: char,
+    x: usize,
+    y: usize,
+}
 
-    for (y, line) in input.lines().enumerate() {
-        let mut current_number: Option<PartNumber> = None;
+// Numbers and symbols as the two sides of a graph, with an edge wherever
+// they touch. Edges are sets of ids so a number reaching a symbol from
+// several of its cells is still only one neighbour.
+struct Schematic {
+    numbers: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+    // number id -> symbol ids
+    number_edges: Vec<BTreeSet<usize>>,
+    // symbol id -> number ids
+    symbol_edges: Vec<BTreeSet<usize>>,
+}
 
-        for (x, c) in line.chars().enumerate() {
-            let tile: Tile = Tile::from(c);
+impl Schematic {
+    fn new(input: &str, lexing: Lexing) -> Self {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
 
-            match tile {
-                Tile::Symbol(_) => {
-                    if let Some(number) = current_number.take() {
-                        numbers.push(number);
-                    }
-                    symbol_map.insert((x, y), tile);
-                }
-                Tile::Num(num) => {
-                    if let Some(number) = &mut current_number {
+        for (y, line) in input.lines().enumerate() {
+            let chars = line.chars().collect::<Vec<_>>();
+            let digit_after = |x: usize| chars.get(x + 1).is_some_and(char::is_ascii_digit);
+            let mut current_number: Option<PartNumber> = None;
+
+            for (x, c) in chars.iter().copied().enumerate() {
+                match (Tile::from(c), &mut current_number) {
+                    (Tile::Num(num), Some(number)) => {
                         number.add_onto(x, num);
-                    } else {
-                        current_number = Some(PartNumber::new(x, y, num as usize));
+                        continue;
+                    }
+                    (Tile::Num(num), None) => {
+                        current_number = Some(PartNumber::new(numbers.len(), x, y, num));
+                        continue;
                     }
+                    (Tile::Blank, Some(number))
+                        if c == '.'
+                            && lexing.decimals
+                            && number.point.is_none()
+                            && digit_after(x) =>
+                    {
+                        number.add_point(x);
+                        continue;
+                    }
+                    (Tile::Symbol('-'), None) if lexing.signed && digit_after(x) => {
+                        // a sign that is also a symbol sits next to its number rather than in it
+                        if lexing.sign_is_symbol {
+                            symbols.push(Symbol {
+                                id: symbols.len(),
+                                kind: c,
+                                x,
+                                y,
+                            });
+                        }
+                        let start = if lexing.sign_is_symbol { x + 1 } else { x };
+                        current_number = Some(PartNumber {
+                            negative: true,
+                            ..PartNumber::new(numbers.len(), start, y, 0)
+                        });
+                        continue;
+                    }
+                    (Tile::Symbol(kind), _) => symbols.push(Symbol {
+                        id: symbols.len(),
+                        kind,
+                        x,
+                        y,
+                    }),
+                    (Tile::Blank, _) => {}
+                }
+
+                if let Some(number) = current_number.take() {
+                    numbers.push(number);
                 }
-                Tile::Blank => {
-                    if let Some(number) = current_number.take() {
-                        numbers.push(number);
+            }
+
+            if let Some(num) = current_number {
+                numbers.push(num);
+            }
+        }
+
+        let symbols_at = symbols
+            .iter()
+            .map(|symbol| ((symbol.x, symbol.y), symbol.id))
+            .collect::<BTreeMap<_, _>>();
+        let mut number_edges = vec![BTreeSet::new(); numbers.len()];
+        let mut symbol_edges = vec![BTreeSet::new(); symbols.len()];
+
+        for number in &numbers {
+            for (x, y) in number.cells() {
+                for coord in Coord(x, y).surrounding() {
+                    if let Some(symbol) = symbols_at.get(&coord) {
+                        number_edges[number.id].insert(*symbol);
+                        symbol_edges[*symbol].insert(number.id);
                     }
                 }
-            };
+            }
         }
 
-        if let Some(num) = current_number {
-            numbers.push(num);
+        Self {
+            numbers,
+            symbols,
+            number_edges,
+            symbol_edges,
         }
     }
 
-    numbers
+    fn symbols_next_to(&self, number: &PartNumber) -> Vec<&Symbol> {
+        self.number_edges[number.id]
+            .iter()
+            .map(|id| &self.symbols[*id])
+            .collect()
+    }
+
+    fn numbers_next_to_symbol(&self, symbol: &Symbol) -> Vec<&PartNumber> {
+        self.symbol_edges[symbol.id]
+            .iter()
+            .map(|id| &self.numbers[*id])
+            .collect()
+    }
+
+    // numbers touching at least one symbol `kind` accepts
+    fn numbers_next_to(&self, kind: impl Fn(char) -> bool) -> Vec<&PartNumber> {
+        self.numbers
+            .iter()
+            .filter(|number| {
+                self.symbols_next_to(number)
+                    .iter()
+                    .any(|symbol| kind(symbol.kind))
+            })
+            .collect()
+    }
+
+    // every `kind` symbol with exactly `count` numbers around it
+    fn gears(&self, kind: char, count: usize) -> Vec<(&Symbol, Vec<&PartNumber>)> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.kind == kind)
+            .map(|symbol| (symbol, self.numbers_next_to_symbol(symbol)))
+            .filter(|(_, numbers)| numbers.len() == count)
+            .collect()
+    }
+}
+
+fn part1(input: &str) -> usize {
+    Schematic::new(input, Lexing::default())
+        .numbers_next_to(|_| true)
         .iter()
-        .filter(|n| n.symbol_close(&symbol_map))
         .map(|n| n.number)
-        .sum::<usize>()
+        .sum()
 }
 
 fn part2(input: &str) -> usize {
-    let mut numbers = Vec::new();
-    let mut stars = Vec::new();
+    Schematic::new(input, Lexing::default())
+        .gears('*', 2)
+        .iter()
+        .map(|(_, numbers)| numbers.iter().map(|n| n.number).product::<usize>())
+        .sum()
+}
 
-    for (y, line) in input.lines().enumerate() {
-        let mut current_number: Option<PartNumber> = None;
+// the same two answers when numbers can have signs and decimal points
+fn part1_with(input: &str, lexing: Lexing) -> Decimal {
+    Schematic::new(input, lexing)
+        .numbers_next_to(|_| true)
+        .iter()
+        .map(|n| n.value())
+        .sum()
+}
 
-        for (x, c) in line.chars().enumerate() {
-            let tile: Tile = Tile::from(c);
+fn part2_with(input: &str, lexing: Lexing) -> Decimal {
+    Schematic::new(input, lexing)
+        .gears('*', 2)
+        .iter()
+        .map(|(_, numbers)| numbers.iter().map(|n| n.value()).product::<Decimal>())
+        .sum()
+}
 
-            match tile {
-                Tile::Symbol(c) => {
-                    if let Some(number) = current_number.take() {
-                        numbers.push(number);
-                    }
-                    if c == '*' {
-                        stars.push((x, y));
-                    }
-                }
-                Tile::Num(num) => {
-                    if let Some(number) = &mut current_number {
-                        number.add_onto(x, num);
-                    } else {
-                        current_number = Some(PartNumber::new(x, y, num as usize));
-                    }
-                }
-                Tile::Blank => {
-                    if let Some(number) = current_number.take() {
-                        numbers.push(number);
-                    }
-                }
-            };
-        }
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
 
-        if let Some(num) = current_number {
-            numbers.push(num);
+    let mut lexing = Lexing::default();
+    let mut graph = false;
+    for flag in std::env::args().skip(1) {
+        match flag.as_str() {
+            "--dot" => graph = true,
+            "--signed" => lexing.signed = true,
+            "--decimals" => lexing.decimals = true,
+            "--sign-is-symbol" => lexing.sign_is_symbol = true,
+            _ => return Err(anyhow!("unknown flag {}", flag)),
         }
     }
 
-    let mut numbers_map: BTreeMap<(usize, usize), &PartNumber> = BTreeMap::new();
-
-    for number in &numbers {
-        for x in number.x_start..=number.x_end {
-            numbers_map.insert((x, number.y), &number);
-        }
+    if graph {
+        print!("{}", dot::dot(&Schematic::new(input, lexing), '*', 2));
+        return Ok(());
     }
 
-    stars
-        .into_iter()
-        .filter_map(|(x, y)| {
-            let mut numbers = Coord(x, y)
-                .surrounding()
-                .filter_map(|coord| numbers_map.get(&coord))
-                .collect::<Vec<_>>();
-            numbers.dedup();
-
-            (numbers.len() == 2).then(|| numbers.into_iter().map(|n| n.number).product::<usize>())
-        })
-        .sum()
-}
-
-fn main() {
-    let input = include_str!("../input.txt");
-    println!("part1: {}", part1(input));
-    println!("part2: {}", part2(input));
+    if lexing == Lexing::default() {
+        println!("part1: {}", part1(input));
+        println!("part2: {}", part2(input));
+    } else {
+        println!("part1: {}", part1_with(input, lexing));
+        println!("part2: {}", part2_with(input, lexing));
+    }
+    Ok(())
 }
 
 #[test]
@@ -203,6 +337,158 @@ fn example_works() {
 ...$.*....
 .664.598.."#;
     assert_eq!(part1(input), 4361);
+    assert_eq!(part2(input), 467835);
+}
+
+#[test]
+fn queries_work() {
+    let input = r#"12.3
+.#..
+4*56
+..7."#;
+    let schematic = Schematic::new(input, Lexing::default());
+
+    let hashed = schematic
+        .numbers_next_to(|kind| kind == '#')
+        .iter()
+        .map(|n| n.number)
+        .collect::<Vec<_>>();
+    assert_eq!(hashed, vec![12, 4, 56]);
+
+    let fifty_six = &schematic.numbers[3];
+    assert_eq!(fifty_six.number, 56);
+    let kinds = schematic
+        .symbols_next_to(fifty_six)
+        .iter()
+        .map(|s| s.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, vec!['#', '*']);
+    assert!(schematic.symbols_next_to(&schematic.numbers[1]).is_empty());
+
+    // the star touches 4, 56 and 7, the hash 12, 4 and 56
+    assert!(schematic.gears('*', 2).is_empty());
+    assert_eq!(schematic.gears('*', 3).len(), 1);
+    assert_eq!(schematic.gears('#', 3).len(), 1);
+}
+
+#[test]
+fn gear_layouts_work() {
+    let layouts = [
+        // a number running along the top touches the gear three times over
+        ("123\n.*.\n..4", 123 * 4),
+        ("123\n.*.\n4.5", 0),
+        // the same value twice is still two numbers
+        ("5.5\n.*.", 25),
+        ("5*5", 25),
+        // up against the edge of the grid
+        ("*3\n4.", 12),
+        (".4\n3*", 12),
+        // one number shared by two gears
+        ("1*2*3", 2 + 6),
+        // wrapping around a corner from above and the side
+        ("12.\n..*\n..3", 36),
+        // touching diagonally at both ends of the number
+        ("1.1\n.*.\n...", 1),
+    ];
+
+    for (input, ratio) in layouts {
+        assert_eq!(part2(input), ratio, "{}", input);
+    }
+    assert_eq!(part1("123\n.#."), 123);
+}
+
+#[test]
+fn lexing_works() {
+    let values = |schematic: &Schematic| {
+        schematic
+            .numbers
+            .iter()
+            .map(|n| n.value().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let signed = Lexing {
+        signed: true,
+        ..Lexing::default()
+    };
+    let schematic = Schematic::new("-467", signed);
+    assert_eq!(values(&schematic), vec!["-467"]);
+    assert!(schematic.numbers_next_to(|_| true).is_empty());
+
+    let schematic = Schematic::new(
+        "-467",
+        Lexing {
+            sign_is_symbol: true,
+            ..signed
+        },
+    );
+    assert_eq!(values(&schematic), vec!["-467"]);
+    assert_eq!(schematic.numbers_next_to(|kind| kind == '-').len(), 1);
+
+    // after a digit a `-` is just a symbol
+    let schematic = Schematic::new("3-4..-", signed);
+    assert_eq!(values(&schematic), vec!["3", "4"]);
+    assert_eq!(schematic.symbols.len(), 2);
+
+    let decimals = Lexing {
+        decimals: true,
+        ..Lexing::default()
+    };
+    let schematic = Schematic::new("1.5*..2..3.4.5", decimals);
+    assert_eq!(values(&schematic), vec!["1.5", "2", "3.4", "5"]);
+    assert_eq!(schematic.gears('*', 1).len(), 1);
+
+    let schematic = Schematic::new(
+        "-1.25",
+        Lexing {
+            decimals: true,
+            ..signed
+        },
+    );
+    assert_eq!(values(&schematic), vec!["-1.25"]);
+
+    let schematic = Schematic::new("-1.5", Lexing::default());
+    assert_eq!(values(&schematic), vec!["1", "5"]);
+}
+
+#[test]
+fn exact_values_work() {
+    let decimals = Lexing {
+        decimals: true,
+        ..Lexing::default()
+    };
+    // a float would give 0.020000000000000004
+    assert_eq!(part2_with("0.1*0.2", decimals).to_string(), "0.02");
+    assert_eq!(part1_with("1.50*0.25", decimals).to_string(), "1.75");
+    assert_eq!(part1_with("1.50*.", decimals).to_string(), "1.50");
+
+    let signed = Lexing {
+        signed: true,
+        decimals: true,
+        ..Lexing::default()
+    };
+    assert_eq!(part1_with("-0.5#2", signed).to_string(), "1.5");
+    assert_eq!(part2_with("-0.05*3", signed).to_string(), "-0.15");
+
+    // well past where a float keeps every digit
+    let big = "9007199254740993*.\n9007199254740993..";
+    assert_eq!(part1_with(big, decimals).to_string(), "18014398509481986");
+}
+
+#[test]
+fn dot_works() {
+    let schematic = Schematic::new("1*2\n...\n\"..3", Lexing::default());
+    let graph = dot::dot(&schematic, '*', 2);
+
+    assert!(graph.starts_with("graph schematic {"));
+    assert!(graph.contains(
+        r#"s0 [label="*\n(1, 0)", shape=box, pos="72,0", style=filled, fillcolor=gold];"#
+    ));
+    assert!(graph.contains(r#"s1 [label="\"\n(0, 2)", shape=box, pos="0,-144"];"#));
+    assert!(graph.contains(r#"n2 [label="3\n(3, 2)", pos="216,-144", style=dashed, color=gray];"#));
+    assert!(graph.contains("n0 -- s0;"));
+    assert!(graph.contains("n1 -- s0;"));
+    assert_eq!(graph.matches(" -- ").count(), 2);
 }
 
 #[test]
@@ -0,0 +1,227 @@
+use std::{ops::Range, str::FromStr};
+
+use anyhow::{anyhow, Context, Error, Result};
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+    println!("part1: {}", part1(input)?);
+    println!("part2: {}", part2(input)?);
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Mapping {
+    source: Range<i64>,
+    offset: i64,
+}
+
+impl FromStr for Mapping {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut nums = s.split_whitespace().map(|num| {
+            num.parse::<i64>()
+                .context(format!("couldn't parse num {}", num))
+        });
+        let dest = nums.next().context("couldn't get dest")??;
+        let source = nums.next().context("couldn't get source")??;
+        let len = nums.next().context("couldn't get len")??;
+
+        Ok(Self {
+            source: source..source + len,
+            offset: dest - source,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Map {
+    // sorted by source start so ranges can be walked left to right
+    mappings: Vec<Mapping>,
+}
+
+impl FromStr for Map {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // seed-to-soil map:
+        // 50 98 2
+        let mut mappings = s
+            .trim()
+            .lines()
+            .skip(1)
+            .map(|line| line.parse::<Mapping>())
+            .collect::<Result<Vec<_>>>()
+            .context(format!("couldn't parse map {}", s))?;
+        mappings.sort_by_key(|mapping| mapping.source.start);
+
+        Ok(Self { mappings })
+    }
+}
+
+impl Map {
+    fn apply(&self, num: i64) -> i64 {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.source.contains(&num))
+            .map_or(num, |mapping| num + mapping.offset)
+    }
+
+    fn apply_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        let mut out = Vec::new();
+        let mut cursor = range.start;
+
+        for mapping in &self.mappings {
+            if cursor >= range.end {
+                break;
+            }
+            if mapping.source.end <= cursor {
+                continue;
+            }
+            if mapping.source.start >= range.end {
+                break;
+            }
+
+            if cursor < mapping.source.start {
+                out.push(cursor..mapping.source.start);
+                cursor = mapping.source.start;
+            }
+
+            let end = range.end.min(mapping.source.end);
+            out.push(cursor + mapping.offset..end + mapping.offset);
+            cursor = end;
+        }
+
+        if cursor < range.end {
+            out.push(cursor..range.end);
+        }
+
+        out
+    }
+}
+
+struct Almanac {
+    seeds: Vec<i64>,
+    maps: Vec<Map>,
+}
+
+impl FromStr for Almanac {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut sections = s.trim().split("\n\n");
+        let seeds = sections
+            .next()
+            .and_then(|seeds| seeds.strip_prefix("seeds:"))
+            .context("couldn't get seeds")?
+            .split_whitespace()
+            .map(|num| {
+                num.parse::<i64>()
+                    .context(format!("couldn't parse seed {}", num))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let maps = sections
+            .map(|section| section.parse::<Map>())
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { seeds, maps })
+    }
+}
+
+impl Almanac {
+    fn location(&self, seed: i64) -> i64 {
+        self.maps.iter().fold(seed, |num, map| map.apply(num))
+    }
+
+    fn locations(&self, ranges: Vec<Range<i64>>) -> Vec<Range<i64>> {
+        self.maps.iter().fold(ranges, |ranges, map| {
+            ranges
+                .into_iter()
+                .flat_map(|range| map.apply_range(range))
+                .collect()
+        })
+    }
+}
+
+fn part1(input: &str) -> Result<i64> {
+    let almanac = input.parse::<Almanac>()?;
+
+    almanac
+        .seeds
+        .iter()
+        .map(|seed| almanac.location(*seed))
+        .min()
+        .context("no seeds")
+}
+
+fn part2(input: &str) -> Result<i64> {
+    let almanac = input.parse::<Almanac>()?;
+    let ranges = almanac
+        .seeds
+        .chunks(2)
+        .map(|pair| match pair {
+            [start, len] => Ok(*start..start + len),
+            _ => Err(anyhow!("seed ranges must come in pairs")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    almanac
+        .locations(ranges)
+        .into_iter()
+        .map(|range| range.start)
+        .min()
+        .context("no seed ranges")
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = r#"seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4"#;
+
+#[test]
+fn part1_works() {
+    assert_eq!(part1(EXAMPLE).unwrap(), 35);
+}
+
+#[test]
+fn part2_works() {
+    assert_eq!(part2(EXAMPLE).unwrap(), 46);
+}
+
+#[test]
+fn apply_range_splits() {
+    let map: Map = "test map:\n50 98 2\n52 50 48".parse().unwrap();
+    assert_eq!(map.apply_range(40..100), vec![40..50, 52..100, 50..52]);
+    assert_eq!(map.apply_range(0..10), vec![0..10]);
+    assert_eq!(map.apply_range(99..105), vec![51..52, 100..105]);
+}
@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Error, Result};
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+    println!("part1: {}", part1(input)?);
+    println!("part2: {}", part2(input)?);
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Race {
+    time: u64,
+    distance: u64,
+}
+
+impl Race {
+    // in u128 so times past 2^32 can't overflow the product
+    fn beats(&self, hold: u64) -> bool {
+        hold as u128 * (self.time - hold) as u128 > self.distance as u128
+    }
+
+    // hold * (time - hold) > distance is a downward parabola, so the winning
+    // holds are everything strictly between the roots of
+    // hold^2 - time * hold + distance = 0
+    fn ways_to_win(&self) -> u64 {
+        let time = self.time as u128;
+        let Some(discriminant) = (time * time).checked_sub(4 * self.distance as u128) else {
+            return 0;
+        };
+
+        // integer sqrt keeps the root exact, then nudge onto the first winning hold
+        let mut low = ((time - discriminant.isqrt()) / 2) as u64;
+        while low <= self.time / 2 && !self.beats(low) {
+            low += 1;
+        }
+        while low > 0 && self.beats(low - 1) {
+            low -= 1;
+        }
+
+        if low > self.time / 2 {
+            return 0;
+        }
+
+        // the parabola is symmetric around time / 2
+        self.time - 2 * low + 1
+    }
+}
+
+fn parse_line<'a>(line: Option<&'a str>, name: &str) -> Result<std::str::SplitWhitespace<'a>> {
+    Ok(line
+        .context(format!("couldn't get {name} line"))?
+        .strip_prefix(name)
+        .and_then(|line| line.strip_prefix(':'))
+        .context(format!("couldn't get {name} values"))?
+        .split_whitespace())
+}
+
+struct Races(Vec<Race>);
+
+impl FromStr for Races {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut lines = s.trim().lines();
+        let times = parse_line(lines.next(), "Time")?.collect::<Vec<_>>();
+        let distances = parse_line(lines.next(), "Distance")?.collect::<Vec<_>>();
+        if times.len() != distances.len() {
+            return Err(anyhow!(
+                "{} times but {} distances",
+                times.len(),
+                distances.len()
+            ));
+        }
+
+        let races = times
+            .into_iter()
+            .zip(distances)
+            .map(|(time, distance)| {
+                Ok(Race {
+                    time: time
+                        .parse()
+                        .context(format!("couldn't parse time {time}"))?,
+                    distance: distance
+                        .parse()
+                        .context(format!("couldn't parse distance {distance}"))?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self(races))
+    }
+}
+
+impl Races {
+    // the kerning was wrong, all the columns are one big race
+    fn concatenated(&self) -> Result<Race> {
+        fn join(nums: impl Iterator<Item = u64>) -> Result<u64> {
+            let joined = nums.map(|num| num.to_string()).collect::<String>();
+            joined
+                .parse()
+                .map_err(|_| anyhow!("couldn't join race into {joined}"))
+        }
+
+        Ok(Race {
+            time: join(self.0.iter().map(|race| race.time))?,
+            distance: join(self.0.iter().map(|race| race.distance))?,
+        })
+    }
+}
+
+fn part1(input: &str) -> Result<u64> {
+    let races = input.parse::<Races>()?;
+    races
+        .0
+        .iter()
+        .map(Race::ways_to_win)
+        .try_fold(1u64, |product, ways| product.checked_mul(ways))
+        .context("product of ways to win overflowed")
+}
+
+fn part2(input: &str) -> Result<u64> {
+    let races = input.parse::<Races>()?;
+    Ok(races.concatenated()?.ways_to_win())
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = r#"Time:      7  15   30
+Distance:  9  40  200"#;
+
+#[test]
+fn part1_works() {
+    assert_eq!(part1(EXAMPLE).unwrap(), 288);
+}
+
+#[test]
+fn part2_works() {
+    assert_eq!(part2(EXAMPLE).unwrap(), 71503);
+}
+
+#[test]
+fn ways_to_win_boundaries() {
+    let brute = |race: &Race| (0..=race.time).filter(|hold| race.beats(*hold)).count() as u64;
+
+    // roots at exactly 10 and 20, neither of which wins
+    let race = Race {
+        time: 30,
+        distance: 200,
+    };
+    assert_eq!(race.ways_to_win(), 9);
+
+    // a single touching root is a tie, not a win
+    let race = Race {
+        time: 4,
+        distance: 4,
+    };
+    assert_eq!(race.ways_to_win(), 0);
+
+    let race = Race {
+        time: 0,
+        distance: 0,
+    };
+    assert_eq!(race.ways_to_win(), 0);
+
+    for time in 0..60 {
+        for distance in 0..=(time * time / 4 + 1) {
+            let race = Race { time, distance };
+            assert_eq!(race.ways_to_win(), brute(&race), "{race:?}");
+        }
+    }
+}
+
+#[test]
+fn mismatched_rows_are_an_error() {
+    assert!(part1("Time: 7 15 30\nDistance: 9").is_err());
+    assert!(part1("Time: 7\nDistance: 9 40").is_err());
+}
+
+#[test]
+fn long_races_work() {
+    let race = Race {
+        time: 5_000_000_000,
+        distance: 0,
+    };
+    assert_eq!(race.ways_to_win(), 4_999_999_999);
+
+    let race = Race {
+        time: u64::MAX,
+        distance: u64::MAX,
+    };
+    assert!(race.ways_to_win() > 0);
+
+    // six races of a billion ways each can't fit in a u64
+    let input = "Time: 1000000001 1000000001 1000000001 1000000001 1000000001 1000000001\n\
+                 Distance: 0 0 0 0 0 0";
+    assert!(part1(input).is_err());
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+
+mod packed;
+mod parser;
+
+const NO_JOKE: &str = "23456789TJQKA";
+// jokers count for least on their own
+const JOKES: &str = "J23456789TQKA";
+
+fn main() -> Result<()> {
+    let input = include_str!("../input.txt");
+
+    println!("part1: {}", packed::winnings(input, NO_JOKE, None)?);
+    println!("part2: {}", packed::winnings(input, JOKES, Some('J'))?);
+
+    Ok(())
+}
+
+#[test]
+fn example_works() {
+    let input = r#"32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483"#;
+    assert_eq!(packed::winnings(input, NO_JOKE, None).unwrap(), 6440);
+    assert_eq!(packed::winnings(input, JOKES, Some('J')).unwrap(), 5905);
+}
+
+#[test]
+fn key_orders_hands() {
+    let key_of = |hand: &str, order: &str, wild: Option<char>| {
+        let (key, _) = packed::parse(&format!("{} 0", hand), order, wild).unwrap();
+        key
+    };
+
+    assert!(key_of("AAAAA", NO_JOKE, None) > key_of("22223", NO_JOKE, None));
+    assert!(key_of("33332", NO_JOKE, None) > key_of("2AAAA", NO_JOKE, None));
+    assert!(key_of("KK677", NO_JOKE, None) > key_of("KTJJT", NO_JOKE, None));
+    // jokers fill in the best type but lose ties as the weakest card
+    let joker = Some('J');
+    assert!(key_of("KTJJT", JOKES, joker) > key_of("QQQJA", JOKES, joker));
+    assert!(key_of("JJJJJ", JOKES, joker) < key_of("22222", JOKES, joker));
+    assert!(key_of("JJJJJ", JOKES, joker) > key_of("AAAAK", JOKES, joker));
+    // the wild is whatever card is named, not whichever is weakest
+    assert!(key_of("2JJJ3", JOKES, Some('2')) < key_of("22JJJ", JOKES, Some('2')));
+    assert!(packed::parse("AAAA 1", NO_JOKE, None).is_err());
+}
@@ -0,0 +1,71 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use anyhow::Result;
+
+use crate::{Hand, HandType, Jokes, NoJoke, Rules};
+
+// every hand in the order it was dealt, with its rank once they're sorted
+pub fn ranked<T: Rules>(input: &str) -> Result<Vec<(Hand<T>, u32)>> {
+    let hands = input
+        .lines()
+        .map(|l| l.parse::<Hand<T>>())
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut order = (0..hands.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| hands[*a].cmp(&hands[*b]));
+    let mut ranks = vec![0; hands.len()];
+    for (rank, i) in (1..).zip(order) {
+        ranks[i] = rank;
+    }
+
+    Ok(hands.into_iter().zip(ranks).collect())
+}
+
+fn faces<T>(hand: &Hand<T>) -> String {
+    hand.cards.iter().map(|card| card.face).collect()
+}
+
+// Both rule sets side by side for every hand, a `*` marking the hands that
+// changed type once jokers went wild, then how many hands of each type
+// there were under each.
+pub fn explain(input: &str) -> Result<String> {
+    let part1 = ranked::<NoJoke>(input)?;
+    let part2 = ranked::<Jokes>(input)?;
+
+    let mut out = format!(
+        "{:<7} {:>5} | {:<15} {:>5} {:>10} | {:<15} {:>6} {:>5} {:>10}\n",
+        "cards", "bid", "part1", "rank", "winnings", "part2", "jokers", "rank", "winnings"
+    );
+    let mut histogram: BTreeMap<&HandType, (usize, usize)> = BTreeMap::new();
+
+    for ((hand1, rank1), (hand2, rank2)) in part1.iter().zip(&part2) {
+        let jokers = hand2
+            .wilds_as
+            .map_or("-".to_string(), |card| card.face.to_string());
+        let changed = if hand1.kind != hand2.kind { "*" } else { "" };
+        let line = format!(
+            "{:<7} {:>5} | {:<15} {:>5} {:>10} | {:<15} {:>6} {:>5} {:>10} {}",
+            faces(hand1),
+            hand1.bid,
+            hand1.kind,
+            rank1,
+            hand1.bid * rank1,
+            hand2.kind,
+            jokers,
+            rank2,
+            hand2.bid * rank2,
+            changed
+        );
+        let _ = writeln!(out, "{}", line.trim_end());
+
+        histogram.entry(&hand1.kind).or_default().0 += 1;
+        histogram.entry(&hand2.kind).or_default().1 += 1;
+    }
+
+    let _ = writeln!(out, "\n{:<15} {:>5} {:>5}", "type", "part1", "part2");
+    for (kind, (part1, part2)) in histogram.iter().rev() {
+        let _ = writeln!(out, "{:<15} {:>5} {:>5}", kind, part1, part2);
+    }
+
+    Ok(out)
+}
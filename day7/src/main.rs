@@ -1,145 +1,146 @@
-use anyhow::{anyhow, Context, Error, Result};
-use std::{collections::HashMap, str::FromStr, marker::PhantomData};
+use anyhow::{anyhow, Error, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+    marker::PhantomData,
+    str::FromStr,
+};
+
+mod explain;
+#[cfg(test)]
+mod packed;
+mod parser;
+
+// How a game of Camel Cards is played: which card beats which, which cards
+// are wild, how many make up a hand, and what the wilds turn into.
+trait Rules: Copy + Ord + Hash {
+    // weakest first
+    const ORDER: &'static str;
+    const WILD: &'static str = "";
+    const HAND_SIZE: usize = 5;
+
+    // `groups` is every non wild card with how many of it there are, biggest
+    // group first and the stronger card first between groups of a size. By
+    // default the wilds all join the biggest group, or make up their own when
+    // the hand is nothing but wilds. Gives back the card the wilds became.
+    fn assign_wilds(
+        groups: &mut Vec<(Card<Self>, usize)>,
+        wilds: &[Card<Self>],
+    ) -> Option<Card<Self>> {
+        if wilds.is_empty() {
+            return None;
+        }
 
+        match groups.first_mut() {
+            Some((card, count)) => {
+                *count += wilds.len();
+                Some(*card)
+            }
+            None => {
+                let wild = *wilds.iter().max()?;
+                groups.push((wild, wilds.len()));
+                Some(wild)
+            }
+        }
+    }
+}
+
+// every face turns up once in ORDER and every wild is one of them
+fn rules_are_valid<T: Rules>() -> bool {
+    let faces = T::ORDER.chars().collect::<HashSet<_>>();
+    faces.len() == T::ORDER.chars().count() && T::WILD.chars().all(|c| faces.contains(&c))
+}
 
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
 struct NoJoke;
+
+impl Rules for NoJoke {
+    const ORDER: &'static str = "23456789TJQKA";
+}
+
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
 struct Jokes;
 
+impl Rules for Jokes {
+    const ORDER: &'static str = "J23456789TQKA";
+    const WILD: &'static str = "J";
+}
+
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
-enum Card<T> {
-    Joker,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    Ten,
-    Jack,
-    Queen,
-    King,
-    Ace,
-    Unused(PhantomData<T>),
-}
-
-impl TryFrom<char> for Card<NoJoke> {
+struct Card<T> {
+    // how many faces come before it in `T::ORDER`
+    rank: usize,
+    face: char,
+    rules: PhantomData<T>,
+}
+
+impl<T: Rules> TryFrom<char> for Card<T> {
     type Error = Error;
 
     fn try_from(s: char) -> std::result::Result<Self, Self::Error> {
-        Ok(match s {
-            '2' => Card::Two,
-            '3' => Card::Three,
-            '4' => Card::Four,
-            '5' => Card::Five,
-            '6' => Card::Six,
-            '7' => Card::Seven,
-            '8' => Card::Eight,
-            '9' => Card::Nine,
-            'T' => Card::Ten,
-            'J' => Card::Jack,
-            'Q' => Card::Queen,
-            'K' => Card::King,
-            'A' => Card::Ace,
-            _ => return Err(anyhow!("couldn't parse card: {}", s)),
+        debug_assert!(rules_are_valid::<T>(), "bad rules: {:?}", T::ORDER);
+        Ok(Self {
+            rank: T::ORDER
+                .chars()
+                .position(|c| c == s)
+                .ok_or(anyhow!("couldn't parse card: {}", s))?,
+            face: s,
+            rules: PhantomData,
         })
     }
 }
 
-impl TryFrom<char> for Card<Jokes> {
-    type Error = Error;
-
-    fn try_from(s: char) -> std::result::Result<Self, Self::Error> {
-        Ok(match s {
-            '2' => Card::Two,
-            '3' => Card::Three,
-            '4' => Card::Four,
-            '5' => Card::Five,
-            '6' => Card::Six,
-            '7' => Card::Seven,
-            '8' => Card::Eight,
-            '9' => Card::Nine,
-            'T' => Card::Ten,
-            'J' => Card::Joker,
-            'Q' => Card::Queen,
-            'K' => Card::King,
-            'A' => Card::Ace,
-            _ => return Err(anyhow!("couldn't parse card: {}", s)),
-        })
+impl<T: Rules> Card<T> {
+    fn is_wild(&self) -> bool {
+        T::WILD.contains(self.face)
     }
 }
 
-#[derive(Debug, PartialOrd, Ord, PartialEq, Eq)]
-enum HandType<T> {
-    HighCard([Card<T>; 5]),
-    Pair([Card<T>; 5]),         // high low kickers AA B C D E
-    TwoPair([Card<T>; 5]),      // high low kickers AA BB D
-    ThreeOfAKind([Card<T>; 5]), // AAA B C
-    FullHouse([Card<T>; 5]),    // AAA BB
-    FourOfAKind([Card<T>; 5]),  // AAAA B
-    FiveOfAKind([Card<T>; 5]),
-}
-
-impl From<[Card<NoJoke>; 5]> for HandType<NoJoke> {
-    fn from(cards: [Card<NoJoke>; 5]) -> Self {
-        let mut count: HashMap<&Card<NoJoke>, usize> = Default::default();
-        for card in &cards {
-            *count.entry(card).or_insert(0) += 1;
+// How many of each card the hand has once wilds are assigned, biggest group
+// first. Comparing these from the front ranks any hand size the way the
+// usual five card types rank: five of a kind over four over a full house
+// and so on.
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Hash)]
+struct HandType(Vec<usize>);
+
+impl HandType {
+    // along with what any wilds were taken to be
+    fn new<T: Rules>(cards: &[Card<T>]) -> (Self, Option<Card<T>>) {
+        let mut count: HashMap<Card<T>, usize> = Default::default();
+        let mut wilds = vec![];
+        for card in cards {
+            if card.is_wild() {
+                wilds.push(*card);
+            } else {
+                *count.entry(*card).or_insert(0) += 1;
+            }
         }
 
-        let mut vals = count.into_iter().collect::<Vec<(&Card<NoJoke>, usize)>>();
+        let mut vals = count.into_iter().collect::<Vec<_>>();
         vals.sort_by_key(|(card, count)| (*count, *card));
         vals.reverse();
+        let wilds_as = T::assign_wilds(&mut vals, &wilds);
 
-        match vals[..] {
-            [(a, 5)] => HandType::FiveOfAKind(cards),
-            [(a, 4), (b, 1)] => HandType::FourOfAKind(cards),
-            [(a, 3), (b, 2)] => HandType::FullHouse(cards),
-            [(a, 3), (b, 1), (c, 1)] => HandType::ThreeOfAKind(cards),
-            [(a, 2), (b, 2), (c, 1)] => HandType::TwoPair(cards),
-            [(a, 2), (b, 1), (c, 1), (d, 1)] => HandType::Pair(cards),
-            [(a, 1), (b, 1), (c, 1), (d, 1), (e, 1)] => HandType::HighCard(cards),
-            _ => {
-                unreachable!()
-            }
-        }
+        let mut counts = vals.into_iter().map(|(_, count)| count).collect::<Vec<_>>();
+        counts.sort_by(|a, b| b.cmp(a));
+        (Self(counts), wilds_as)
     }
 }
 
-impl From<[Card<Jokes>; 5]> for HandType<Jokes> {
-    fn from(cards: [Card<Jokes>; 5]) -> Self {
-        let mut count: HashMap<&Card<Jokes>, usize> = Default::default();
-        for card in &cards {
-            *count.entry(card).or_insert(0) += 1;
-        }
-
-        let jokers = count.remove(&Card::Joker);
-
-        let mut vals = count.into_iter().collect::<Vec<(&Card<Jokes>, usize)>>();
-        vals.sort_by_key(|(card, count)| (*count, *card));
-        vals.reverse();
-        if let Some((_, count)) = vals.first_mut() {
-            if let Some(joker_count) = jokers {
-                *count += joker_count
-            }
-        } else {
-            vals.push((&Card::Joker, 5));
-        }
-
-        match vals[..] {
-            [(a, 5)] => HandType::FiveOfAKind(cards),
-            [(a, 4), (b, 1)] => HandType::FourOfAKind(cards),
-            [(a, 3), (b, 2)] => HandType::FullHouse(cards),
-            [(a, 3), (b, 1), (c, 1)] => HandType::ThreeOfAKind(cards),
-            [(a, 2), (b, 2), (c, 1)] => HandType::TwoPair(cards),
-            [(a, 2), (b, 1), (c, 1), (d, 1)] => HandType::Pair(cards),
-            [(a, 1), (b, 1), (c, 1), (d, 1), (e, 1)] => HandType::HighCard(cards),
+impl Display for HandType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0[..] {
+            [5] => f.pad("five of a kind"),
+            [4, 1] => f.pad("four of a kind"),
+            [3, 2] => f.pad("full house"),
+            [3, 1, 1] => f.pad("three of a kind"),
+            [2, 2, 1] => f.pad("two pair"),
+            [2, 1, 1, 1] => f.pad("one pair"),
+            [1, 1, 1, 1, 1] => f.pad("high card"),
             _ => {
-                println!("{:?}", vals);
-                unreachable!()
+                let counts = self.0.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+                f.pad(&counts.join("-"))
             }
         }
     }
@@ -147,58 +148,40 @@ impl From<[Card<Jokes>; 5]> for HandType<Jokes> {
 
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq)]
 struct Hand<T> {
-    cards: HandType<T>,
+    kind: HandType,
+    cards: Vec<Card<T>>,
     bid: u32,
+    wilds_as: Option<Card<T>>,
 }
 
-impl FromStr for Hand<NoJoke> {
-    type Err = Error;
-
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let (cards, bid) = s.split_once(" ").context("couldn't get cards and bid")?;
-        let mut cards  = cards.chars().map(Card::try_from);
-
-        let cards: [Card<NoJoke>; 5] = [
-            cards.next().context("couldn't get card")??,
-            cards.next().context("couldn't get card")??,
-            cards.next().context("couldn't get card")??,
-            cards.next().context("couldn't get card")??,
-            cards.next().context("couldn't get card")??,
-        ];
-
-        Ok(Self {
-            cards: cards.into(),
-            bid: bid.parse()?,
-        })
-    }
-}
-
-impl FromStr for Hand<Jokes> {
+impl<T: Rules> FromStr for Hand<T> {
     type Err = Error;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let (cards, bid) = s.split_once(" ").context("couldn't get cards and bid")?;
-        let mut cards  = cards.chars().map(Card::try_from);
-
-        let cards: [Card<Jokes>; 5] = [
-            cards.next().context("couldn't get card")??,
-            cards.next().context("couldn't get card")??,
-            cards.next().context("couldn't get card")??,
-            cards.next().context("couldn't get card")??,
-            cards.next().context("couldn't get card")??,
-        ];
+        let (cards, bid) = parser::parse_hand(s, Card::try_from)?;
+        if cards.len() != T::HAND_SIZE {
+            return Err(anyhow!(
+                "hand has {} cards, expected {}: {}",
+                cards.len(),
+                T::HAND_SIZE,
+                s
+            ));
+        }
 
+        let (kind, wilds_as) = HandType::new(&cards);
         Ok(Self {
-            cards: cards.into(),
-            bid: bid.parse()?,
+            kind,
+            cards,
+            bid,
+            wilds_as,
         })
     }
 }
 
-fn part1(input: &str) -> Result<u32> {
+fn winnings<T: Rules>(input: &str) -> Result<u32> {
     let mut hands = input
         .lines()
-        .map(|l| l.parse::<Hand<NoJoke>>())
+        .map(|l| l.parse::<Hand<T>>())
         .collect::<Result<Vec<_>>>()?;
     hands.sort();
 
@@ -209,35 +192,255 @@ fn part1(input: &str) -> Result<u32> {
         .sum::<u32>())
 }
 
-fn part2(input: &str) -> Result<u32> {
-    let mut hands = input
-        .lines()
-        .map(|l| l.parse::<Hand<Jokes>>())
-        .collect::<Result<Vec<_>>>()?;
-    hands.sort();
+fn part1(input: &str) -> Result<u32> {
+    winnings::<NoJoke>(input)
+}
 
-    Ok(hands
-        .into_iter()
-        .enumerate()
-        .map(|(rank, hand)| hand.bid * (rank + 1) as u32)
-        .sum::<u32>())
+fn part2(input: &str) -> Result<u32> {
+    winnings::<Jokes>(input)
 }
 
 fn main() -> Result<()> {
     let input = include_str!("../input.txt");
 
+    if std::env::args().nth(1).as_deref() == Some("--explain") {
+        print!("{}", explain::explain(input)?);
+        return Ok(());
+    }
+
     println!("part1: {}", part1(input)?);
     println!("part2: {}", part2(input)?);
 
     Ok(())
 }
 
+#[cfg(test)]
+const EXAMPLE: &str = r#"32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483"#;
+
 #[test]
 fn part1_works() {
+    assert_eq!(part1(EXAMPLE).unwrap(), 6440);
+}
+
+#[test]
+fn part2_works() {
+    assert_eq!(part2(EXAMPLE).unwrap(), 5905);
+}
+
+#[test]
+fn rules_work() {
+    // jokers still wild but now the strongest card on a tie
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+    struct HighJokers;
+    impl Rules for HighJokers {
+        const ORDER: &'static str = "23456789TQKAJ";
+        const WILD: &'static str = "J";
+    }
+
+    // twos and jacks both wild, but wilds only ever pair up with the
+    // strongest card in the hand rather than the biggest group
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+    struct Deuces;
+    impl Rules for Deuces {
+        const ORDER: &'static str = "23456789TJQKA";
+        const WILD: &'static str = "2J";
+
+        fn assign_wilds(
+            groups: &mut Vec<(Card<Self>, usize)>,
+            wilds: &[Card<Self>],
+        ) -> Option<Card<Self>> {
+            groups.sort_by_key(|(card, count)| (*card, *count));
+            groups.reverse();
+            let card = match groups.first_mut() {
+                Some((card, count)) => {
+                    *count += wilds.len();
+                    *card
+                }
+                None => {
+                    groups.push((wilds[0], wilds.len()));
+                    wilds[0]
+                }
+            };
+            groups.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+            Some(card)
+        }
+    }
+
+    let hand = |line: &str| line.parse::<Hand<HighJokers>>().unwrap();
+    assert!(hand("JJJJJ 1") > hand("AAAAA 1"));
+    assert!(hand("QQQJA 1") > hand("QQQQ2 1"));
+
+    let hand = |line: &str| line.parse::<Hand<Deuces>>().unwrap();
+    assert_eq!(hand("2JAK3 1").kind, HandType(vec![3, 1, 1]));
+    // the wilds go to the ace, not the pair of threes
+    assert_eq!(hand("233A4 1").kind, HandType(vec![2, 2, 1]));
+    assert_eq!(hand("22JJ2 1").kind, HandType(vec![5]));
+    assert_eq!(hand("233A4 1").wilds_as.map(|card| card.face), Some('A'));
+    assert!("1JAK3 1".parse::<Hand<Deuces>>().is_err());
+}
+
+#[test]
+fn hand_sizes_work() {
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+    struct Three;
+    impl Rules for Three {
+        const ORDER: &'static str = NoJoke::ORDER;
+        const HAND_SIZE: usize = 3;
+    }
+
+    let hand = |line: &str| line.parse::<Hand<Three>>().unwrap();
+    assert!(hand("222 1") > hand("AAK 1"));
+    assert!(hand("AAK 1") > hand("KKA 1"));
+    assert!(hand("KK2 1") > hand("AKQ 1"));
+    assert!("AAKK 1".parse::<Hand<Three>>().is_err());
+
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+    struct SevenJokes;
+    impl Rules for SevenJokes {
+        const ORDER: &'static str = Jokes::ORDER;
+        const WILD: &'static str = Jokes::WILD;
+        const HAND_SIZE: usize = 7;
+    }
+
+    let hand = |line: &str| line.parse::<Hand<SevenJokes>>().unwrap();
+    assert_eq!(hand("AAAKKKQ 1").kind, HandType(vec![3, 3, 1]));
+    assert_eq!(hand("AAAKKQJ 1").kind, HandType(vec![4, 2, 1]));
+    assert!(hand("2222345 1") > hand("AAAKKKQ 1"));
+    assert!(hand("AAAKKKQ 1") > hand("AAAKKQQ 1"));
+    assert_eq!(hand("JJJJJJJ 1").kind.to_string(), "7");
+    assert_eq!(hand("AAKKQQ2 1").kind.to_string(), "2-2-2-1");
+
+    // a deck running high to low turns the example's ties around
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+    struct Reversed;
+    impl Rules for Reversed {
+        const ORDER: &'static str = "AKQJT98765432";
+    }
+    assert_eq!(winnings::<Reversed>(EXAMPLE).unwrap(), 6833);
+}
+
+#[test]
+fn packed_keys_agree() {
+    // ties on type broken by card, whole hands repeated with different
+    // bids, and jokers standing in for every type
     let input = r#"32T3K 765
 T55J5 684
 KK677 28
 KTJJT 220
-QQQJA 483"#;
-    assert_eq!(part1(input).unwrap(), 6440);
+QQQJA 483
+KK677 29
+JJJJJ 1
+22222 2
+J2222 3
+2J222 4
+AAAAK 5
+AAAAJ 6
+JJ234 7
+2345J 8
+23456 9
+65432 10
+T5J5T 11
+TT55J 12
+JKKKK 13
+QJJQ2 14
+QQJQ2 15"#;
+
+    fn ranked<T: Rules>(input: &str) -> Vec<u32> {
+        let mut hands = input
+            .lines()
+            .map(|l| l.parse::<Hand<T>>().unwrap())
+            .collect::<Vec<_>>();
+        hands.sort();
+        hands.into_iter().map(|hand| hand.bid).collect()
+    }
+
+    fn agree<T: Rules>(input: &str) {
+        let packed = packed::ranked(input, T::ORDER, T::WILD.chars().next()).unwrap();
+        assert_eq!(ranked::<T>(input), packed);
+        assert_eq!(
+            winnings::<T>(input).unwrap(),
+            packed::winnings(input, T::ORDER, T::WILD.chars().next()).unwrap()
+        );
+    }
+
+    agree::<NoJoke>(input);
+    agree::<Jokes>(input);
+    assert_ne!(ranked::<NoJoke>(input), ranked::<Jokes>(input));
+}
+
+#[test]
+fn explain_works() {
+    let part1 = explain::ranked::<NoJoke>(EXAMPLE).unwrap();
+    let part2 = explain::ranked::<Jokes>(EXAMPLE).unwrap();
+
+    // T55J5, a joker making four fives
+    let (hand, rank) = &part1[1];
+    assert_eq!(hand.kind, HandType(vec![3, 1, 1]));
+    assert_eq!(hand.wilds_as, None);
+    assert_eq!((*rank, hand.bid * rank), (4, 2736));
+    let (hand, rank) = &part2[1];
+    assert_eq!(hand.kind, HandType(vec![4, 1]));
+    assert_eq!(hand.wilds_as.map(|card| card.face), Some('5'));
+    assert_eq!((*rank, hand.bid * rank), (3, 2052));
+
+    // KK677, nothing to substitute
+    let (hand, rank) = &part2[2];
+    assert_eq!(hand.kind, HandType(vec![2, 2, 1]));
+    assert_eq!(hand.wilds_as, None);
+    assert_eq!((*rank, hand.bid * rank), (2, 56));
+
+    let explained = explain::explain(EXAMPLE).unwrap();
+    let fields = |line: &str| {
+        line.split(|c: char| c == '|' || c.is_whitespace())
+            .filter(|field| !field.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let lines = explained.lines().map(fields).collect::<Vec<_>>();
+    assert_eq!(
+        lines[2],
+        "T55J5 684 three of a kind 4 2736 four of a kind 5 3 2052 *"
+    );
+    assert!(lines.contains(&"four of a kind 0 3".to_string()));
+}
+
+#[test]
+fn multibyte_faces_work() {
+    assert!(rules_are_valid::<NoJoke>());
+    assert!(rules_are_valid::<Jokes>());
+
+    // ranks count faces, not bytes, so ♠ is 3 rather than 9
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+    struct Suits;
+    impl Rules for Suits {
+        const ORDER: &'static str = "♣♦♥♠";
+        const WILD: &'static str = "♣";
+        const HAND_SIZE: usize = 3;
+    }
+    assert!(rules_are_valid::<Suits>());
+    assert_eq!(Card::<Suits>::try_from('♠').unwrap().rank, 3);
+
+    let hand = |line: &str| line.parse::<Hand<Suits>>().unwrap();
+    assert_eq!(hand("♠♥♣ 1").kind, HandType(vec![2, 1]));
+    assert!(hand("♦♦♠ 1") > hand("♣♥♠ 1"));
+    assert!(hand("♥♠♠ 1") > hand("♥♥♠ 1"));
+
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+    struct Doubled;
+    impl Rules for Doubled {
+        const ORDER: &'static str = "23A45A";
+    }
+    assert!(!rules_are_valid::<Doubled>());
+
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+    struct StrayWild;
+    impl Rules for StrayWild {
+        const ORDER: &'static str = "23456789TQKA";
+        const WILD: &'static str = "J";
+    }
+    assert!(!rules_are_valid::<StrayWild>());
 }
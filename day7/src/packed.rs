@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Result};
+
+use crate::parser;
+
+// Everything a hand is ranked on squeezed into one number: the hand type
+// above five 4 bit card ranks in the order they were dealt, so comparing
+// keys is comparing hands. `wild` is the rank of the card that joins the
+// biggest group, if there is one.
+pub fn key(cards: [u32; 5], wild: Option<u32>) -> u32 {
+    let mut counts = [0; 16];
+    for card in cards {
+        counts[card as usize] += 1;
+    }
+
+    let wilds = wild.map_or(0, |wild| std::mem::take(&mut counts[wild as usize]));
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    counts[0] += wilds;
+
+    let kind = match (counts[0], counts[1]) {
+        (5, _) => 6,
+        (4, _) => 5,
+        (3, 2) => 4,
+        (3, _) => 3,
+        (2, 2) => 2,
+        (2, _) => 1,
+        _ => 0,
+    };
+
+    cards.into_iter().fold(kind, |key, card| key << 4 | card)
+}
+
+// 32T3K 765 as its key and bid, `order` being weakest first
+pub fn parse(line: &str, order: &str, wild: Option<char>) -> Result<(u32, u32)> {
+    if order.chars().count() > 16 {
+        return Err(anyhow!("can only pack 16 different cards"));
+    }
+    let rank = |c: char| {
+        order
+            .chars()
+            .position(|card| card == c)
+            .map(|rank| rank as u32)
+            .ok_or(anyhow!("couldn't parse card: {}", c))
+    };
+
+    let (cards, bid) = parser::parse_hand(line, rank)?;
+    let cards = cards
+        .try_into()
+        .map_err(|cards: Vec<u32>| anyhow!("can only pack five cards, not {}", cards.len()))?;
+    let wild = wild.map(rank).transpose()?;
+
+    Ok((key(cards, wild), bid))
+}
+
+// bids in the order the hands rank, weakest first
+pub fn ranked(input: &str, order: &str, wild: Option<char>) -> Result<Vec<u32>> {
+    let mut hands = input
+        .lines()
+        .map(|line| parse(line, order, wild))
+        .collect::<Result<Vec<_>>>()?;
+    hands.sort_unstable();
+
+    Ok(hands.into_iter().map(|(_, bid)| bid).collect())
+}
+
+pub fn winnings(input: &str, order: &str, wild: Option<char>) -> Result<u32> {
+    Ok(ranked(input, order, wild)?
+        .into_iter()
+        .zip(1..)
+        .map(|(bid, rank)| bid * rank)
+        .sum())
+}
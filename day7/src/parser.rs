@@ -0,0 +1,9 @@
+use anyhow::{Context, Result};
+
+// 32T3K 765
+pub fn parse_hand<T>(s: &str, card: impl Fn(char) -> Result<T>) -> Result<(Vec<T>, u32)> {
+    let (cards, bid) = s.split_once(" ").context("couldn't get cards and bid")?;
+    let cards = cards.chars().map(card).collect::<Result<Vec<_>>>()?;
+
+    Ok((cards, bid.parse().context("couldn't get bid")?))
+}